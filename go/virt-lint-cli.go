@@ -122,7 +122,7 @@ func virt_lint_worker(conn *libvirt.Connect, xml string, tags []string) error {
 
 	for i := 0; i < len(warn); i++ {
 		w := warn[i]
-		fmt.Printf("Warning: tags=%v\tdomain=%v\tlevel=%v\tmsg=%s\n", w.Tags, w.Domain, w.Level, w.Msg)
+		fmt.Printf("Warning: tags=%v\tid=%s\tdomain=%v\tlevel=%v\tmsg=%s\tlocations=%v\tdata=%v\n", w.Tags, w.Id, w.Domain, w.Level, w.Msg, w.Locations, w.Data)
 	}
 
 	return nil
@@ -3,7 +3,9 @@
 package VirtLint
 
 import (
+	"encoding/json"
 	"fmt"
+	"time"
 	"unsafe"
 
 	libvirt "libvirt.org/go/libvirt"
@@ -152,6 +154,158 @@ func (vl *VirtLint) Validate(xml string, tags []string, error_on_no_connect bool
 	return nil
 }
 
+type Config struct {
+	ptr *C.VirtLintConfig
+}
+
+func NewConfig() *Config {
+	config := C.virt_lint_config_new()
+	return &Config{ptr: config}
+}
+
+// Load configuration from a TOML file.
+func LoadConfig(path string) (*Config, error) {
+	var vlErr *C.VirtLintError = nil
+
+	cpath := C.CString(path)
+	defer C.free(unsafe.Pointer(cpath))
+
+	config := C.virt_lint_config_load(cpath, &vlErr)
+	if config == nil {
+		return nil, makeError(&vlErr)
+	}
+
+	return &Config{ptr: config}, nil
+}
+
+// Load and merge system, user and project configuration files, whichever
+// exist.
+func LoadDefaultConfig() (*Config, error) {
+	var vlErr *C.VirtLintError = nil
+
+	config := C.virt_lint_config_load_default(&vlErr)
+	if config == nil {
+		return nil, makeError(&vlErr)
+	}
+
+	return &Config{ptr: config}, nil
+}
+
+// Default connection URI, or an empty string if not configured.
+func (config *Config) URI() string {
+	curi := C.virt_lint_config_uri(config.ptr)
+	if curi == nil {
+		return ""
+	}
+	defer C.virt_lint_string_free(curi)
+
+	return C.GoString(curi)
+}
+
+func (config *Config) Free() {
+	C.virt_lint_config_free(config.ptr)
+	config.ptr = nil
+}
+
+func (config *Config) RuleSetLevel(rule string, level WarningLevel) error {
+	var vlErr *C.VirtLintError = nil
+
+	crule := C.CString(rule)
+	defer C.free(unsafe.Pointer(crule))
+
+	if C.virt_lint_config_rule_set_level(config.ptr, crule, C.enum_WarningLevel(level), &vlErr) < 0 {
+		return makeError(&vlErr)
+	}
+
+	return nil
+}
+
+func (config *Config) RuleDisable(rule string) error {
+	var vlErr *C.VirtLintError = nil
+
+	crule := C.CString(rule)
+	defer C.free(unsafe.Pointer(crule))
+
+	if C.virt_lint_config_rule_disable(config.ptr, crule, &vlErr) < 0 {
+		return makeError(&vlErr)
+	}
+
+	return nil
+}
+
+func (config *Config) RuleUnset(rule string) error {
+	var vlErr *C.VirtLintError = nil
+
+	crule := C.CString(rule)
+	defer C.free(unsafe.Pointer(crule))
+
+	if C.virt_lint_config_rule_unset(config.ptr, crule, &vlErr) < 0 {
+		return makeError(&vlErr)
+	}
+
+	return nil
+}
+
+func (config *Config) MinLevelSet(level WarningLevel) error {
+	var vlErr *C.VirtLintError = nil
+
+	clevel := C.enum_WarningLevel(level)
+
+	if C.virt_lint_config_min_level_set(config.ptr, &clevel, &vlErr) < 0 {
+		return makeError(&vlErr)
+	}
+
+	return nil
+}
+
+func (config *Config) MinLevelClear() error {
+	var vlErr *C.VirtLintError = nil
+
+	if C.virt_lint_config_min_level_set(config.ptr, nil, &vlErr) < 0 {
+		return makeError(&vlErr)
+	}
+
+	return nil
+}
+
+func (config *Config) KeepGoingSet(keepGoing bool) error {
+	var vlErr *C.VirtLintError = nil
+
+	if C.virt_lint_config_keep_going_set(config.ptr, C.bool(keepGoing), &vlErr) < 0 {
+		return makeError(&vlErr)
+	}
+
+	return nil
+}
+
+func (vl *VirtLint) ConfigSet(config *Config) error {
+	var vlErr *C.VirtLintError = nil
+
+	if C.virt_lint_config_set(vl.ptr, config.ptr, &vlErr) < 0 {
+		return makeError(&vlErr)
+	}
+
+	return nil
+}
+
+// Set locale of warning messages, e.g. "cs_CZ.UTF-8". Pass an empty string to
+// use the locale of the environment.
+func (vl *VirtLint) LocaleSet(locale string) error {
+	var vlErr *C.VirtLintError = nil
+	var clocale *C.char = nil
+
+	if locale != "" {
+		clocale = C.CString(locale)
+		defer C.free(unsafe.Pointer(clocale))
+	}
+
+	if C.virt_lint_locale_set(vl.ptr, clocale, &vlErr) < 0 {
+		return makeError(&vlErr)
+	}
+
+	return nil
+}
+
 type WarningDomain int
 
 const (
@@ -191,11 +345,30 @@ func (e WarningLevel) String() string {
 	}
 }
 
+type VirtLintWarningLocation struct {
+	XPath  string
+	Line   uint
+	Column uint
+}
+
+func (l VirtLintWarningLocation) String() string {
+	if l.Line == 0 {
+		return l.XPath
+	}
+	if l.Column == 0 {
+		return fmt.Sprintf("%s:%d", l.XPath, l.Line)
+	}
+	return fmt.Sprintf("%s:%d:%d", l.XPath, l.Line, l.Column)
+}
+
 type VirtLintWarning struct {
-	Tags   []string
-	Domain WarningDomain
-	Level  WarningLevel
-	Msg    string
+	Tags      []string
+	Id        string
+	Domain    WarningDomain
+	Level     WarningLevel
+	Msg       string
+	Locations []VirtLintWarningLocation
+	Data      map[string]interface{}
 }
 
 func (vl *VirtLint) GetWarnings() ([]VirtLintWarning, error) {
@@ -222,15 +395,225 @@ func (vl *VirtLint) GetWarnings() ([]VirtLintWarning, error) {
 			tags[j] = C.GoString(cwarn_tags[j])
 		}
 
+		id := C.GoString(cwarn.id)
 		msg := C.GoString(cwarn.msg)
 
+		cwarn_locations := unsafe.Slice(cwarn.locations, cwarn.nlocations)
+
+		locations := make([]VirtLintWarningLocation, cwarn.nlocations)
+		for j := 0; j < len(cwarn_locations); j++ {
+			locations[j] = VirtLintWarningLocation{
+				XPath:  C.GoString(cwarn_locations[j].xpath),
+				Line:   uint(cwarn_locations[j].line),
+				Column: uint(cwarn_locations[j].column),
+			}
+		}
+
+		data := map[string]interface{}{}
+		err := json.Unmarshal([]byte(C.GoString(cwarn.data)), &data)
+		if err != nil {
+			return []VirtLintWarning{}, err
+		}
+
 		warnings[i] = VirtLintWarning{
-			Tags:   tags,
-			Domain: WarningDomain(cwarn.domain),
-			Level:  WarningLevel(cwarn.level),
-			Msg:    msg,
+			Tags:      tags,
+			Id:        id,
+			Domain:    WarningDomain(cwarn.domain),
+			Level:     WarningLevel(cwarn.level),
+			Msg:       msg,
+			Locations: locations,
+			Data:      data,
 		}
 	}
 
 	return warnings, nil
 }
+
+type RuleOutcome int
+
+const (
+	RAN     RuleOutcome = RuleOutcome(C.Ran)
+	SKIPPED RuleOutcome = RuleOutcome(C.Skipped)
+	FAILED  RuleOutcome = RuleOutcome(C.Failed)
+)
+
+func (e RuleOutcome) String() string {
+	switch e {
+	case RAN:
+		return "RAN"
+	case SKIPPED:
+		return "SKIPPED"
+	case FAILED:
+		return "FAILED"
+	default:
+		return fmt.Sprintf("%d", int(e))
+	}
+}
+
+type RuleRun struct {
+	Id        string
+	Tags      []string
+	Engine    ValidatorEngine
+	Outcome   RuleOutcome
+	Reason    string
+	Duration  time.Duration
+	Warnings  int
+	Path      string
+	Line      int
+	Traceback string
+}
+
+// Get rules executed by the last Validate(), with their outcome. Reason is
+// empty for rules that ran, otherwise it says why the rule was skipped or
+// how it failed. Warnings is the number of warnings the rule emitted. For
+// failed Lua and Python validators, Path, Line (0 if unknown) and Traceback
+// tell where the error occurred.
+func (vl *VirtLint) GetRules() ([]RuleRun, error) {
+	var vlErr *C.VirtLintError = nil
+	var crules *C.CRuleRun = nil
+
+	ncrules := C.virt_lint_get_rules(vl.ptr, &crules, &vlErr)
+	defer C.virt_lint_rules_free(&crules, &ncrules)
+
+	if ncrules < 0 {
+		return []RuleRun{}, makeError(&vlErr)
+	}
+
+	crules_slice := unsafe.Slice(crules, ncrules)
+
+	rules := make([]RuleRun, ncrules)
+
+	for i := 0; i < len(crules_slice); i++ {
+		crule := crules_slice[i]
+		crule_tags := unsafe.Slice(crule.tags, crule.ntags)
+
+		tags := make([]string, crule.ntags)
+		for j := 0; j < len(crule_tags); j++ {
+			tags[j] = C.GoString(crule_tags[j])
+		}
+
+		reason := ""
+		if crule.reason != nil {
+			reason = C.GoString(crule.reason)
+		}
+
+		path := ""
+		if crule.path != nil {
+			path = C.GoString(crule.path)
+		}
+
+		traceback := ""
+		if crule.traceback != nil {
+			traceback = C.GoString(crule.traceback)
+		}
+
+		rules[i] = RuleRun{
+			Id:        C.GoString(crule.id),
+			Tags:      tags,
+			Engine:    ValidatorEngine(crule.engine),
+			Outcome:   RuleOutcome(crule.outcome),
+			Reason:    reason,
+			Duration:  time.Duration(crule.duration_us) * time.Microsecond,
+			Warnings:  int(crule.nwarnings),
+			Path:      path,
+			Line:      int(crule.line),
+			Traceback: traceback,
+		}
+	}
+
+	return rules, nil
+}
+
+type ValidatorEngine int
+
+const (
+	BUILTIN ValidatorEngine = ValidatorEngine(C.Builtin)
+	LUA     ValidatorEngine = ValidatorEngine(C.Lua)
+	PYTHON  ValidatorEngine = ValidatorEngine(C.Python)
+)
+
+func (e ValidatorEngine) String() string {
+	switch e {
+	case BUILTIN:
+		return "BUILTIN"
+	case LUA:
+		return "LUA"
+	case PYTHON:
+		return "PYTHON"
+	default:
+		return fmt.Sprintf("%d", int(e))
+	}
+}
+
+type ValidatorInfo struct {
+	Id                      string
+	Engine                  ValidatorEngine
+	Path                    string
+	Tags                    []string
+	Description             string
+	Level                   *WarningLevel
+	NeedsKnown              bool
+	NeedsConnection         bool
+	NeedsCapabilities       bool
+	NeedsDomainCapabilities bool
+}
+
+// List all linting rules. Validator paths are taken from config, if not nil.
+// Path is empty for built-in rules, and Level is nil if unknown. Needs* are
+// valid only if NeedsKnown is true.
+func ListValidators(config *Config) ([]ValidatorInfo, error) {
+	var vlErr *C.VirtLintError = nil
+	var cconfig *C.VirtLintConfig = nil
+	var cvalidators *C.CValidatorInfo = nil
+
+	if config != nil {
+		cconfig = config.ptr
+	}
+
+	ncvalidators := C.virt_lint_list_validators(cconfig, &cvalidators, &vlErr)
+	defer C.virt_lint_validators_free(&cvalidators, &ncvalidators)
+
+	if ncvalidators < 0 {
+		return []ValidatorInfo{}, makeError(&vlErr)
+	}
+
+	cvalidators_slice := unsafe.Slice(cvalidators, ncvalidators)
+
+	validators := make([]ValidatorInfo, ncvalidators)
+
+	for i := 0; i < len(cvalidators_slice); i++ {
+		cinfo := cvalidators_slice[i]
+		cinfo_tags := unsafe.Slice(cinfo.tags, cinfo.ntags)
+
+		tags := make([]string, cinfo.ntags)
+		for j := 0; j < len(cinfo_tags); j++ {
+			tags[j] = C.GoString(cinfo_tags[j])
+		}
+
+		path := ""
+		if cinfo.path != nil {
+			path = C.GoString(cinfo.path)
+		}
+
+		var level *WarningLevel = nil
+		if cinfo.has_level {
+			l := WarningLevel(cinfo.level)
+			level = &l
+		}
+
+		validators[i] = ValidatorInfo{
+			Id:                      C.GoString(cinfo.id),
+			Engine:                  ValidatorEngine(cinfo.engine),
+			Path:                    path,
+			Tags:                    tags,
+			Description:             C.GoString(cinfo.description),
+			Level:                   level,
+			NeedsKnown:              bool(cinfo.has_needs),
+			NeedsConnection:         bool(cinfo.needs_connection),
+			NeedsCapabilities:       bool(cinfo.needs_capabilities),
+			NeedsDomainCapabilities: bool(cinfo.needs_domain_capabilities),
+		}
+	}
+
+	return validators, nil
+}
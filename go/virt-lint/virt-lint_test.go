@@ -15,6 +15,9 @@ func TestMain(t *testing.T) {
 	// TODO - switch to an absolute path, replaced at compile time
 	os.Setenv("VIRT_LINT_LUA_PATH", "../validators_lua:../../validators_lua")
 	os.Setenv("VIRT_LINT_PYTHON_PATH", "../validators_python:../../validators_python")
+	os.Setenv("VIRT_LINT_LOCALE_PATH", "../po:../../po")
+	// Expected messages are in English
+	os.Setenv("LC_ALL", "C")
 }
 
 func getConn(t *testing.T) *libvirt.Connect {
@@ -35,6 +38,24 @@ func closeConn(conn *libvirt.Connect, t *testing.T) {
 	}
 }
 
+// All the NUMA related rules point at the <memory/> element of the "test" domain.
+var memoryLocation = []VirtLint.VirtLintWarningLocation{{"/domain/memory", 4, 3}}
+
+// The "test" domain has 8GiB of memory, while NUMA nodes of the test driver have 2 and 4GiB.
+const numaFitMsg = "Domain memory (8388608 KiB) would not fit into any host NUMA node"
+const numaFreeMsg = "Not enough free memory (8388608 KiB) on any NUMA node"
+
+var numaFitData = map[string]interface{}{
+	"requested_kib":    float64(8388608),
+	"largest_node_kib": float64(4194304),
+}
+
+var numaFreeData = map[string]interface{}{
+	"requested_kib":    float64(8388608),
+	"free_kib":         []interface{}{float64(2048), float64(4096)},
+	"largest_free_kib": float64(4096),
+}
+
 func TestEmpty(t *testing.T) {
 	conn := getConn(t)
 	defer closeConn(conn, t)
@@ -77,6 +98,39 @@ func TestListTags(t *testing.T) {
 	}
 }
 
+func TestListValidators(t *testing.T) {
+	validators, err := VirtLint.ListValidators(nil)
+
+	if err != nil {
+		t.Error(err)
+		return
+	}
+
+	if len(validators) != 12 {
+		t.Errorf("Expected 12 validators, got %d", len(validators))
+		return
+	}
+
+	for _, v := range validators {
+		if v.Id != "numa.free" {
+			continue
+		}
+
+		if v.Engine != VirtLint.BUILTIN || v.Path != "" {
+			t.Errorf("Unexpected engine %v or path %q", v.Engine, v.Path)
+		}
+		if v.Level == nil || *v.Level != VirtLint.ERROR {
+			t.Errorf("Unexpected level %v", v.Level)
+		}
+		if !v.NeedsKnown || !v.NeedsConnection || !v.NeedsCapabilities || v.NeedsDomainCapabilities {
+			t.Errorf("Unexpected needs %v", v)
+		}
+		return
+	}
+
+	t.Errorf("numa.free not listed")
+}
+
 func TestSimple(t *testing.T) {
 	conn := getConn(t)
 	defer closeConn(conn, t)
@@ -114,18 +168,24 @@ func TestSimple(t *testing.T) {
 	}
 
 	expect := []VirtLint.VirtLintWarning{
-		VirtLint.VirtLintWarning{[]string{"TAG_1", "TAG_2"},
-			VirtLint.DOMAIN, VirtLint.ERROR, "Domain would not fit into any host NUMA node"},
-		VirtLint.VirtLintWarning{[]string{"TAG_2"},
-			VirtLint.DOMAIN, VirtLint.ERROR, "Not enough free memory on any NUMA node"},
-		VirtLint.VirtLintWarning{[]string{"common", "common/check_numa"},
-			VirtLint.DOMAIN, VirtLint.ERROR, "Domain would not fit into any host NUMA node"},
-		VirtLint.VirtLintWarning{[]string{"common", "common/check_numa_free"},
-			VirtLint.DOMAIN, VirtLint.ERROR, "Not enough free memory on any NUMA node"},
-		VirtLint.VirtLintWarning{[]string{"common_p", "common_p/check_numa"},
-			VirtLint.DOMAIN, VirtLint.ERROR, "Domain would not fit into any host NUMA node"},
-		VirtLint.VirtLintWarning{[]string{"common_p", "common_p/check_numa_free"},
-			VirtLint.DOMAIN, VirtLint.ERROR, "Not enough free memory on any NUMA node"},
+		VirtLint.VirtLintWarning{[]string{"TAG_1", "TAG_2"}, "numa.fit",
+			VirtLint.DOMAIN, VirtLint.ERROR, numaFitMsg,
+			memoryLocation, numaFitData},
+		VirtLint.VirtLintWarning{[]string{"TAG_2"}, "numa.free",
+			VirtLint.DOMAIN, VirtLint.ERROR, numaFreeMsg,
+			memoryLocation, numaFreeData},
+		VirtLint.VirtLintWarning{[]string{"common", "common/check_numa"}, "common/check_numa",
+			VirtLint.DOMAIN, VirtLint.ERROR, numaFitMsg,
+			memoryLocation, numaFitData},
+		VirtLint.VirtLintWarning{[]string{"common", "common/check_numa_free"}, "common/check_numa_free",
+			VirtLint.DOMAIN, VirtLint.ERROR, numaFreeMsg,
+			memoryLocation, numaFreeData},
+		VirtLint.VirtLintWarning{[]string{"common_p", "common_p/check_numa"}, "common_p/check_numa",
+			VirtLint.DOMAIN, VirtLint.ERROR, numaFitMsg,
+			memoryLocation, numaFitData},
+		VirtLint.VirtLintWarning{[]string{"common_p", "common_p/check_numa_free"}, "common_p/check_numa_free",
+			VirtLint.DOMAIN, VirtLint.ERROR, numaFreeMsg,
+			memoryLocation, numaFreeData},
 	}
 	if !reflect.DeepEqual(warn, expect) {
 		t.Errorf("Warnings don't match:\nexpected = %v\ngot = %v", expect, warn)
@@ -196,17 +256,43 @@ func TestOfflineSimple(t *testing.T) {
 	}
 
 	expect := []VirtLint.VirtLintWarning{
-		VirtLint.VirtLintWarning{[]string{"TAG_1", "TAG_2"},
-			VirtLint.DOMAIN, VirtLint.ERROR, "Domain would not fit into any host NUMA node"},
-		VirtLint.VirtLintWarning{[]string{"common", "common/check_numa"},
-			VirtLint.DOMAIN, VirtLint.ERROR, "Domain would not fit into any host NUMA node"},
-		VirtLint.VirtLintWarning{[]string{"common_p", "common_p/check_numa"},
-			VirtLint.DOMAIN, VirtLint.ERROR, "Domain would not fit into any host NUMA node"},
+		VirtLint.VirtLintWarning{[]string{"TAG_1", "TAG_2"}, "numa.fit",
+			VirtLint.DOMAIN, VirtLint.ERROR, numaFitMsg,
+			memoryLocation, numaFitData},
+		VirtLint.VirtLintWarning{[]string{"common", "common/check_numa"}, "common/check_numa",
+			VirtLint.DOMAIN, VirtLint.ERROR, numaFitMsg,
+			memoryLocation, numaFitData},
+		VirtLint.VirtLintWarning{[]string{"common_p", "common_p/check_numa"}, "common_p/check_numa",
+			VirtLint.DOMAIN, VirtLint.ERROR, numaFitMsg,
+			memoryLocation, numaFitData},
 	}
 	if !reflect.DeepEqual(warn, expect) {
 		t.Errorf("Warnings don't match:\nexpected = %v\ngot = %v", expect, warn)
 		return
 	}
+
+	rules, err := vl.GetRules()
+	if err != nil {
+		t.Error(err)
+		return
+	}
+
+	// Rules that need a connection are skipped
+	for _, r := range rules {
+		switch r.Id {
+		case "numa.fit":
+			if r.Outcome != VirtLint.RAN {
+				t.Errorf("Rule %s not run: %v %s", r.Id, r.Outcome, r.Reason)
+			}
+			if r.Engine != VirtLint.BUILTIN || r.Warnings != 1 {
+				t.Errorf("Unexpected engine %v or warnings %d", r.Engine, r.Warnings)
+			}
+		case "numa.free", "common/check_numa_free", "common_p/check_numa_free":
+			if r.Outcome != VirtLint.SKIPPED || r.Reason != "no connection" {
+				t.Errorf("Rule %s not skipped: %v %s", r.Id, r.Outcome, r.Reason)
+			}
+		}
+	}
 }
 
 func TestOfflineWithError(t *testing.T) {
@@ -283,15 +369,87 @@ func TestOfflineWithError(t *testing.T) {
 	}
 
 	expect := []VirtLint.VirtLintWarning{
-		VirtLint.VirtLintWarning{[]string{"TAG_1", "TAG_2"},
-			VirtLint.DOMAIN, VirtLint.ERROR, "Domain would not fit into any host NUMA node"},
-		VirtLint.VirtLintWarning{[]string{"common", "common/check_numa"},
-			VirtLint.DOMAIN, VirtLint.ERROR, "Domain would not fit into any host NUMA node"},
-		VirtLint.VirtLintWarning{[]string{"common_p", "common_p/check_numa"},
-			VirtLint.DOMAIN, VirtLint.ERROR, "Domain would not fit into any host NUMA node"},
+		VirtLint.VirtLintWarning{[]string{"TAG_1", "TAG_2"}, "numa.fit",
+			VirtLint.DOMAIN, VirtLint.ERROR, numaFitMsg,
+			memoryLocation, numaFitData},
+		VirtLint.VirtLintWarning{[]string{"common", "common/check_numa"}, "common/check_numa",
+			VirtLint.DOMAIN, VirtLint.ERROR, numaFitMsg,
+			memoryLocation, numaFitData},
+		VirtLint.VirtLintWarning{[]string{"common_p", "common_p/check_numa"}, "common_p/check_numa",
+			VirtLint.DOMAIN, VirtLint.ERROR, numaFitMsg,
+			memoryLocation, numaFitData},
 	}
 	if !reflect.DeepEqual(warn, expect) {
 		t.Errorf("Warnings don't match:\nexpected = %v\ngot = %v", expect, warn)
 		return
 	}
+
+	// Keep going does not make missing connection fine
+	config := VirtLint.NewConfig()
+	defer config.Free()
+
+	if err = config.KeepGoingSet(true); err != nil {
+		t.Error(err)
+		return
+	}
+
+	if err = vl.ConfigSet(config); err != nil {
+		t.Error(err)
+		return
+	}
+
+	err = vl.Validate(domxml, []string{}, true)
+	if err == nil {
+		t.Errorf("Expected failure, got success")
+		return
+	}
+
+	rules, err := vl.GetRules()
+	if err != nil {
+		t.Error(err)
+		return
+	}
+
+	for _, r := range rules {
+		switch r.Id {
+		case "common/check_numa_free":
+			if r.Outcome != VirtLint.FAILED {
+				t.Errorf("Rule %s not failed: %v %s", r.Id, r.Outcome, r.Reason)
+			}
+		case "common/check_node_kvm", "common/check_numa":
+			if r.Outcome != VirtLint.RAN {
+				t.Errorf("Rule %s not run: %v %s", r.Id, r.Outcome, r.Reason)
+			}
+		}
+	}
+}
+
+func TestLoadConfig(t *testing.T) {
+	file, err := os.CreateTemp("", "virt-lint-*.toml")
+	if err != nil {
+		t.Fatal(err)
+	}
+	defer os.Remove(file.Name())
+
+	if _, err := file.WriteString("uri = \"test:///default\"\n\n[rules]\n\"TAG_4\" = \"disabled\"\n"); err != nil {
+		t.Fatal(err)
+	}
+	file.Close()
+
+	config, err := VirtLint.LoadConfig(file.Name())
+	if err != nil {
+		t.Error(err)
+		return
+	}
+	defer config.Free()
+
+	if uri := config.URI(); uri != "test:///default" {
+		t.Errorf("URI() == %q, expected \"test:///default\"", uri)
+		return
+	}
+
+	if _, err := VirtLint.LoadConfig("/nonexistent/virt-lint.toml"); err == nil {
+		t.Errorf("Expected failure, got success")
+		return
+	}
 }
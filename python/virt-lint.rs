@@ -27,9 +27,15 @@ impl From<errors::VirtLintError> for PyVirtLintError {
 #[allow(dead_code)]
 pub struct PyVirtLintWarning {
     tags: Vec<String>,
+    #[pyo3(get)]
+    id: String,
     domain: WarningDomain,
     level: WarningLevel,
     msg: String,
+    #[pyo3(get)]
+    locations: Vec<(String, Option<u32>, Option<u32>)>,
+    #[pyo3(get)]
+    data: WarningData,
 }
 
 #[pymethods]
@@ -45,13 +51,143 @@ impl From<&VirtLintWarning> for PyVirtLintWarning {
         let (tags, domain, level, msg) = other.get();
         Self {
             tags: tags.to_vec(),
+            id: other.id().to_string(),
             domain: *domain,
             level: *level,
             msg: msg.to_string(),
+            locations: other
+                .locations()
+                .iter()
+                .map(|l| (l.xpath().to_string(), l.line(), l.column()))
+                .collect(),
+            data: other.data().clone(),
         }
     }
 }
 
+#[pyclass(name = "ValidatorInfo")]
+#[derive(Debug)]
+pub struct PyValidatorInfo {
+    #[pyo3(get)]
+    id: String,
+    #[pyo3(get)]
+    engine: String,
+    #[pyo3(get)]
+    path: Option<std::path::PathBuf>,
+    #[pyo3(get)]
+    tags: Vec<String>,
+    #[pyo3(get)]
+    description: String,
+    #[pyo3(get)]
+    level: Option<String>,
+    #[pyo3(get)]
+    needs_connection: Option<bool>,
+    #[pyo3(get)]
+    needs_capabilities: Option<bool>,
+    #[pyo3(get)]
+    needs_domain_capabilities: Option<bool>,
+}
+
+#[pymethods]
+impl PyValidatorInfo {
+    fn __repr__(slf: Bound<'_, Self>) -> PyResult<String> {
+        let class_name = slf.get_type().name()?;
+        Ok(format!("{}({:?})", class_name, slf.borrow()))
+    }
+}
+
+impl From<&ValidatorInfo> for PyValidatorInfo {
+    fn from(other: &ValidatorInfo) -> Self {
+        Self {
+            id: other.id().to_string(),
+            engine: other.engine().to_string(),
+            path: other.path().map(std::path::Path::to_path_buf),
+            tags: other.tags().to_vec(),
+            description: other.description().to_string(),
+            level: other.level().map(|l| l.to_string()),
+            needs_connection: other.needs_connection(),
+            needs_capabilities: other.needs_capabilities(),
+            needs_domain_capabilities: other.needs_domain_capabilities(),
+        }
+    }
+}
+
+#[pyclass(name = "VirtLintConfig")]
+#[derive(Clone, Debug, Default)]
+pub struct PyVirtLintConfig {
+    config: VirtLintConfig,
+}
+
+#[pymethods]
+impl PyVirtLintConfig {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn __repr__(slf: &Bound<'_, Self>) -> PyResult<String> {
+        let class_name = slf.get_type().name()?;
+        Ok(format!("{}({:?})", class_name, slf.borrow().config))
+    }
+
+    #[staticmethod]
+    fn from_toml(toml: &str) -> PyResult<Self> {
+        Ok(Self {
+            config: VirtLintConfig::from_toml(toml)?,
+        })
+    }
+
+    #[staticmethod]
+    fn load(path: std::path::PathBuf) -> PyResult<Self> {
+        Ok(Self {
+            config: VirtLintConfig::load(&path)?,
+        })
+    }
+
+    #[staticmethod]
+    fn load_default() -> PyResult<Self> {
+        Ok(Self {
+            config: VirtLintConfig::load_default()?,
+        })
+    }
+
+    fn uri(&self) -> Option<String> {
+        self.config.uri().map(str::to_string)
+    }
+
+    fn validator_tags(&self) -> Vec<String> {
+        self.config.validator_tags().to_vec()
+    }
+
+    fn rule_set_level(&mut self, rule: &str, level: &str) -> PyResult<()> {
+        let level: WarningLevel = level.parse()?;
+        self.config.rule_set(rule, RuleSetting::Level(level));
+        Ok(())
+    }
+
+    fn rule_disable(&mut self, rule: &str) {
+        self.config.rule_set(rule, RuleSetting::Disabled);
+    }
+
+    fn rule_unset(&mut self, rule: &str) {
+        self.config.rule_unset(rule);
+    }
+
+    #[pyo3(signature = (level = None))]
+    fn min_level_set(&mut self, level: Option<&str>) -> PyResult<()> {
+        let level = match level {
+            Some(x) => Some(x.parse::<WarningLevel>()?),
+            None => None,
+        };
+        self.config.min_level_set(level);
+        Ok(())
+    }
+
+    fn keep_going_set(&mut self, keep_going: bool) {
+        self.config.keep_going_set(keep_going);
+    }
+}
+
 #[pyclass(name = "VirtLint")]
 pub struct PyVirtLint {
     vl: Arc<Mutex<VirtLint>>,
@@ -104,6 +240,16 @@ impl PyVirtLint {
         Ok(())
     }
 
+    #[pyo3(signature = (locale = None))]
+    fn locale_set(&mut self, locale: Option<&str>) -> PyResult<()> {
+        self.vl.lock().unwrap().locale_set(locale)?;
+        Ok(())
+    }
+
+    fn config_set(&mut self, config: &PyVirtLintConfig) {
+        self.vl.lock().unwrap().config_set(config.config.clone());
+    }
+
     fn validate(
         &mut self,
         domxml: &str,
@@ -128,10 +274,60 @@ impl PyVirtLint {
             .collect()
     }
 
+    /// Executed rules as (id, outcome, reason) tuples. The outcome is "ran", "skipped" or
+    /// "failed", the reason is None for rules that ran. For failed rules, the reason is the
+    /// error, followed by the traceback if there is one.
+    fn rules(&self) -> Vec<(String, String, Option<String>)> {
+        self.vl
+            .lock()
+            .unwrap()
+            .rules()
+            .iter()
+            .map(|r| {
+                let (outcome, reason) = match r.outcome() {
+                    RuleOutcome::Ran => ("ran", None),
+                    RuleOutcome::Skipped(x) => ("skipped", Some(x.clone())),
+                    RuleOutcome::Failed(x) => match x.traceback() {
+                        Some(tb) => ("failed", Some(format!("{x}\n{tb}"))),
+                        None => ("failed", Some(x.to_string())),
+                    },
+                };
+                (r.id().to_string(), outcome.to_string(), reason)
+            })
+            .collect()
+    }
+
+    fn suppressed(&self) -> Vec<(PyVirtLintWarning, Option<String>)> {
+        self.vl
+            .lock()
+            .unwrap()
+            .suppressed()
+            .iter()
+            .map(|s| {
+                (
+                    PyVirtLintWarning::from(s.warning()),
+                    s.reason().map(str::to_string),
+                )
+            })
+            .collect()
+    }
+
     #[staticmethod]
     fn list_validator_tags() -> PyResult<Vec<String>> {
         Ok(VirtLint::list_validator_tags()?)
     }
+
+    #[staticmethod]
+    #[pyo3(signature = (config = None))]
+    fn list_validators(config: Option<&PyVirtLintConfig>) -> PyResult<Vec<PyValidatorInfo>> {
+        let default_config = VirtLintConfig::default();
+        let config = config.map_or(&default_config, |c| &c.config);
+
+        Ok(VirtLint::list_validators(config)?
+            .iter()
+            .map(PyValidatorInfo::from)
+            .collect())
+    }
 }
 
 /// Formats the sum of two numbers as string.
@@ -145,5 +341,7 @@ fn sum_as_string(a: usize, b: usize) -> PyResult<String> {
 fn virt_lint(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(sum_as_string, m)?)?;
     m.add_class::<PyVirtLint>()?;
+    m.add_class::<PyVirtLintConfig>()?;
+    m.add_class::<PyValidatorInfo>()?;
     Ok(())
 }
@@ -6,6 +6,9 @@ import virt_lint
 for tag in virt_lint.VirtLint.list_validator_tags():
     print(tag)
 
+for validator in virt_lint.VirtLint.list_validators():
+    print(validator)
+
 conn = libvirt.open("test:///default")
 
 dom = conn.lookupByName("test")
@@ -16,3 +19,6 @@ vl.validate(dom.XMLDesc(), [], False)
 
 for warning in vl.warnings():
     print(warning)
+
+for rule in vl.rules():
+    print(rule)
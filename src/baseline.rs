@@ -0,0 +1,188 @@
+/* SPDX-License-Identifier: LGPL-3.0-or-later */
+
+use crate::*;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+const BASELINE_VERSION: u32 = 1;
+
+/// A single known warning, as recorded in [`Baseline`].
+///
+/// Warnings are identified by the rule ID, the domain (name and UUID) and XPaths of their
+/// locations. Neither the message nor line numbers are recorded as those change too often.
+#[derive(Clone, Debug, PartialEq, PartialOrd, Ord, Eq, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    rule: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    domain_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    domain_uuid: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    locations: Vec<String>,
+}
+
+impl BaselineEntry {
+    fn new(
+        w: &VirtLintWarning,
+        domain_name: &Option<String>,
+        domain_uuid: &Option<String>,
+    ) -> Self {
+        Self {
+            rule: w.id.clone(),
+            domain_name: domain_name.clone(),
+            domain_uuid: domain_uuid.clone(),
+            locations: w.locations.iter().map(|l| l.xpath.clone()).collect(),
+        }
+    }
+
+    /// Rule ID, see [`VirtLintWarning::id()`].
+    pub fn rule(&self) -> &str {
+        &self.rule
+    }
+
+    /// Name of the domain the warning was produced for.
+    pub fn domain_name(&self) -> Option<&str> {
+        self.domain_name.as_deref()
+    }
+
+    /// UUID of the domain the warning was produced for.
+    pub fn domain_uuid(&self) -> Option<&str> {
+        self.domain_uuid.as_deref()
+    }
+
+    /// XPaths of warning locations, see [`VirtLintWarning::locations()`].
+    pub fn locations(&self) -> &[String] {
+        &self.locations
+    }
+
+    fn same_domain(&self, domain_name: &Option<String>, domain_uuid: &Option<String>) -> bool {
+        // UUID is more reliable than name, but it's not always present
+        match (&self.domain_uuid, domain_uuid) {
+            (Some(a), Some(b)) => a == b,
+            _ => &self.domain_name == domain_name,
+        }
+    }
+
+    fn same_warning(&self, other: &BaselineEntry) -> bool {
+        self.rule == other.rule
+            && self.locations == other.locations
+            && self.same_domain(&other.domain_name, &other.domain_uuid)
+    }
+}
+
+/// Set of known warnings.
+///
+/// When set via [`VirtLint::baseline_set()`], warnings recorded in the baseline are not
+/// reported by [`VirtLint::warnings()`]. Conversely, baseline entries that no longer occur are
+/// reported by [`VirtLint::baseline_stale()`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Baseline {
+    version: u32,
+    entries: Vec<BaselineEntry>,
+}
+
+impl Default for Baseline {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Baseline {
+    pub fn new() -> Self {
+        Self {
+            version: BASELINE_VERSION,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Record `warnings` produced for domain XML `domxml`.
+    ///
+    /// Call this multiple times to record warnings for several domains.
+    pub fn add(&mut self, domxml: &str, warnings: &[VirtLintWarning]) -> VirtLintResult<()> {
+        let (domain_name, domain_uuid) = domain_identity(domxml)?;
+
+        for w in warnings {
+            let entry = BaselineEntry::new(w, &domain_name, &domain_uuid);
+
+            if !self.entries.iter().any(|e| e.same_warning(&entry)) {
+                self.entries.push(entry);
+            }
+        }
+
+        self.entries.sort();
+        Ok(())
+    }
+
+    /// Obtain recorded entries.
+    pub fn entries(&self) -> &[BaselineEntry] {
+        &self.entries
+    }
+
+    /// Parse baseline from JSON.
+    pub fn from_json(json: &str) -> VirtLintResult<Self> {
+        let baseline: Self = serde_json::from_str(json)?;
+
+        if baseline.version != BASELINE_VERSION {
+            return Err(VirtLintError::InvalidArgument(
+                "Unsupported baseline version",
+            ));
+        }
+
+        Ok(baseline)
+    }
+
+    /// Format baseline as JSON.
+    pub fn to_json(&self) -> VirtLintResult<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Load baseline from a file.
+    pub fn load(path: &Path) -> VirtLintResult<Self> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// Save baseline into a file.
+    pub fn save(&self, path: &Path) -> VirtLintResult<()> {
+        let mut json = self.to_json()?;
+        json.push('\n');
+        Ok(std::fs::write(path, json)?)
+    }
+
+    /// Drop `warnings` produced for domain XML `domxml` that are recorded in the baseline, and
+    /// return baseline entries of the domain that no longer occur. Only entries of `rules` that
+    /// ran can be stale: a rule that was not selected, was skipped or failed says nothing about
+    /// whether its warnings are gone.
+    pub(crate) fn apply(
+        &self,
+        domxml: &str,
+        warnings: &mut Vec<VirtLintWarning>,
+        rules: &[RuleRun],
+    ) -> VirtLintResult<Vec<BaselineEntry>> {
+        let (domain_name, domain_uuid) = domain_identity(domxml)?;
+
+        let ran = |id: &str| {
+            rules
+                .iter()
+                .any(|r| r.id() == id && *r.outcome() == RuleOutcome::Ran)
+        };
+
+        let mut stale: Vec<&BaselineEntry> = self
+            .entries
+            .iter()
+            .filter(|e| e.same_domain(&domain_name, &domain_uuid) && ran(&e.rule))
+            .collect();
+
+        warnings.retain(|w| {
+            let entry = BaselineEntry::new(w, &domain_name, &domain_uuid);
+
+            if self.entries.iter().any(|e| e.same_warning(&entry)) {
+                stale.retain(|e| !e.same_warning(&entry));
+                return false;
+            }
+
+            true
+        });
+
+        Ok(stale.into_iter().cloned().collect())
+    }
+}
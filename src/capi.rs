@@ -181,6 +181,202 @@ pub extern "C" fn virt_lint_validate(
     0
 }
 
+#[no_mangle]
+pub extern "C" fn virt_lint_config_new() -> *mut VirtLintConfig {
+    Box::into_raw(Box::new(VirtLintConfig::new()))
+}
+
+#[no_mangle]
+pub extern "C" fn virt_lint_config_load(
+    path: *const std::ffi::c_char,
+    err: *mut *mut VirtLintError,
+) -> *mut VirtLintConfig {
+    virt_lint_error_free(err);
+
+    check_not_null!(path, err, std::ptr::null_mut());
+
+    let path_string = unsafe { c_chars_to_string!(path) };
+
+    match VirtLintConfig::load(std::path::Path::new(&path_string)) {
+        Ok(config) => Box::into_raw(Box::new(config)),
+        Err(x) => {
+            err_set(err, x);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn virt_lint_config_load_default(
+    err: *mut *mut VirtLintError,
+) -> *mut VirtLintConfig {
+    virt_lint_error_free(err);
+
+    match VirtLintConfig::load_default() {
+        Ok(config) => Box::into_raw(Box::new(config)),
+        Err(x) => {
+            err_set(err, x);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn virt_lint_config_uri(config: *const VirtLintConfig) -> *mut std::ffi::c_char {
+    if config.is_null() {
+        return std::ptr::null_mut();
+    }
+
+    let config = unsafe { &*config };
+
+    match config.uri() {
+        Some(uri) => string_to_mut_c_chars!(uri),
+        None => std::ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn virt_lint_config_free(config: *mut VirtLintConfig) {
+    if !config.is_null() {
+        std::mem::drop(unsafe { Box::from_raw(config) });
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn virt_lint_config_rule_set_level(
+    config: *mut VirtLintConfig,
+    rule: *const std::ffi::c_char,
+    level: WarningLevel,
+    err: *mut *mut VirtLintError,
+) -> std::ffi::c_int {
+    virt_lint_error_free(err);
+
+    check_not_null!(config, err, -1);
+    check_not_null!(rule, err, -1);
+
+    let config = unsafe { &mut *config };
+    let rule_string = unsafe { c_chars_to_string!(rule) };
+
+    config.rule_set(&rule_string, RuleSetting::Level(level));
+    0
+}
+
+#[no_mangle]
+pub extern "C" fn virt_lint_config_rule_disable(
+    config: *mut VirtLintConfig,
+    rule: *const std::ffi::c_char,
+    err: *mut *mut VirtLintError,
+) -> std::ffi::c_int {
+    virt_lint_error_free(err);
+
+    check_not_null!(config, err, -1);
+    check_not_null!(rule, err, -1);
+
+    let config = unsafe { &mut *config };
+    let rule_string = unsafe { c_chars_to_string!(rule) };
+
+    config.rule_set(&rule_string, RuleSetting::Disabled);
+    0
+}
+
+#[no_mangle]
+pub extern "C" fn virt_lint_config_rule_unset(
+    config: *mut VirtLintConfig,
+    rule: *const std::ffi::c_char,
+    err: *mut *mut VirtLintError,
+) -> std::ffi::c_int {
+    virt_lint_error_free(err);
+
+    check_not_null!(config, err, -1);
+    check_not_null!(rule, err, -1);
+
+    let config = unsafe { &mut *config };
+    let rule_string = unsafe { c_chars_to_string!(rule) };
+
+    config.rule_unset(&rule_string);
+    0
+}
+
+#[no_mangle]
+pub extern "C" fn virt_lint_config_min_level_set(
+    config: *mut VirtLintConfig,
+    level: *const WarningLevel,
+    err: *mut *mut VirtLintError,
+) -> std::ffi::c_int {
+    virt_lint_error_free(err);
+
+    check_not_null!(config, err, -1);
+
+    let config = unsafe { &mut *config };
+    let new_level = if level.is_null() {
+        None
+    } else {
+        Some(unsafe { *level })
+    };
+
+    config.min_level_set(new_level);
+    0
+}
+
+#[no_mangle]
+pub extern "C" fn virt_lint_config_keep_going_set(
+    config: *mut VirtLintConfig,
+    keep_going: bool,
+    err: *mut *mut VirtLintError,
+) -> std::ffi::c_int {
+    virt_lint_error_free(err);
+
+    check_not_null!(config, err, -1);
+
+    let config = unsafe { &mut *config };
+
+    config.keep_going_set(keep_going);
+    0
+}
+
+#[no_mangle]
+pub extern "C" fn virt_lint_config_set(
+    vl: *mut VirtLint,
+    config: *const VirtLintConfig,
+    err: *mut *mut VirtLintError,
+) -> std::ffi::c_int {
+    virt_lint_error_free(err);
+
+    check_not_null!(vl, err, -1);
+    check_not_null!(config, err, -1);
+
+    let vl = unsafe { &mut *vl };
+    let config = unsafe { &*config };
+
+    vl.config_set(config.clone());
+    0
+}
+
+#[no_mangle]
+pub extern "C" fn virt_lint_locale_set(
+    vl: *mut VirtLint,
+    locale: *const std::ffi::c_char,
+    err: *mut *mut VirtLintError,
+) -> std::ffi::c_int {
+    virt_lint_error_free(err);
+
+    check_not_null!(vl, err, -1);
+
+    let vl = unsafe { &mut *vl };
+    let new_locale = if locale.is_null() {
+        None
+    } else {
+        Some(unsafe { c_chars_to_string!(locale) })
+    };
+
+    if let Err(x) = vl.locale_set(new_locale.as_deref()) {
+        err_set(err, x);
+        return -1;
+    }
+
+    0
+}
+
 #[no_mangle]
 pub extern "C" fn virt_lint_list_tags(
     tags: *mut *mut *mut std::ffi::c_char,
@@ -212,14 +408,26 @@ pub extern "C" fn virt_lint_list_tags(
     me.len().try_into().unwrap()
 }
 
+#[repr(C)]
+#[derive(Debug, Clone)]
+pub struct CVirtLintWarningLocation {
+    xpath: *mut std::ffi::c_char,
+    line: std::ffi::c_uint,
+    column: std::ffi::c_uint,
+}
+
 #[repr(C)]
 #[derive(Debug, Clone)]
 pub struct CVirtLintWarning {
     tags: *mut *mut std::ffi::c_char,
     ntags: usize,
+    id: *mut std::ffi::c_char,
     domain: WarningDomain,
     level: WarningLevel,
     msg: *mut std::ffi::c_char,
+    locations: *mut CVirtLintWarningLocation,
+    nlocations: usize,
+    data: *mut std::ffi::c_char,
 }
 
 #[no_mangle]
@@ -254,12 +462,29 @@ pub extern "C" fn virt_lint_get_warnings(
 
         let mut me = ManuallyDrop::new(v);
 
+        let mut l: Vec<_> = w
+            .locations
+            .iter()
+            .map(|l| CVirtLintWarningLocation {
+                xpath: string_to_mut_c_chars!(l.xpath.as_str()),
+                line: l.line.unwrap_or_default(),
+                column: l.column.unwrap_or_default(),
+            })
+            .collect();
+        l.shrink_to_fit();
+
+        let mut me_l = ManuallyDrop::new(l);
+
         c_warn.push(CVirtLintWarning {
             tags: me.as_mut_ptr(),
             ntags: me.len(),
+            id: string_to_mut_c_chars!(w.id.as_str()),
             domain: w.domain,
             level: w.level,
             msg: string_to_mut_c_chars!(w.msg.as_str()),
+            locations: me_l.as_mut_ptr(),
+            nlocations: me_l.len(),
+            data: string_to_mut_c_chars!(serde_json::to_string(&w.data).unwrap()),
         })
     });
 
@@ -285,7 +510,15 @@ pub extern "C" fn virt_lint_warnings_free(
         let tags = unsafe { Vec::from_raw_parts(s.tags, s.ntags, s.ntags) };
 
         tags.iter().for_each(|t| virt_lint_string_free(*t));
-        virt_lint_string_free(s.msg)
+        virt_lint_string_free(s.id);
+        virt_lint_string_free(s.msg);
+        virt_lint_string_free(s.data);
+
+        let locations = unsafe { Vec::from_raw_parts(s.locations, s.nlocations, s.nlocations) };
+
+        locations
+            .iter()
+            .for_each(|l| virt_lint_string_free(l.xpath));
     });
 
     unsafe {
@@ -293,3 +526,237 @@ pub extern "C" fn virt_lint_warnings_free(
         *nwarnings = 0;
     }
 }
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub enum CRuleOutcome {
+    Ran,
+    Skipped,
+    Failed,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone)]
+pub struct CRuleRun {
+    id: *mut std::ffi::c_char,
+    tags: *mut *mut std::ffi::c_char,
+    ntags: usize,
+    engine: ValidatorEngine,
+    outcome: CRuleOutcome,
+    reason: *mut std::ffi::c_char,
+    duration_us: u64,
+    nwarnings: usize,
+    path: *mut std::ffi::c_char,
+    line: u32,
+    traceback: *mut std::ffi::c_char,
+}
+
+#[no_mangle]
+pub extern "C" fn virt_lint_get_rules(
+    vl: *mut VirtLint,
+    rules: *mut *mut CRuleRun,
+    err: *mut *mut VirtLintError,
+) -> isize {
+    virt_lint_error_free(err);
+
+    check_not_null!(vl, err, -1);
+    check_not_null!(rules, err, -1);
+
+    let vl = unsafe { &mut *vl };
+
+    let runs = vl.rules();
+    if runs.is_empty() {
+        unsafe {
+            *rules = std::ptr::null_mut();
+        }
+        return 0;
+    }
+
+    let mut c_runs: Vec<CRuleRun> = runs
+        .iter()
+        .map(|r| {
+            let mut v: Vec<_> = r
+                .tags()
+                .iter()
+                .map(|s| string_to_mut_c_chars!(s.as_str()))
+                .collect();
+            v.shrink_to_fit();
+
+            let mut me = ManuallyDrop::new(v);
+
+            let (outcome, reason) = match r.outcome() {
+                RuleOutcome::Ran => (CRuleOutcome::Ran, std::ptr::null_mut()),
+                RuleOutcome::Skipped(x) => {
+                    (CRuleOutcome::Skipped, string_to_mut_c_chars!(x.as_str()))
+                }
+                RuleOutcome::Failed(x) => {
+                    (CRuleOutcome::Failed, string_to_mut_c_chars!(x.message()))
+                }
+            };
+
+            let error = r.failed();
+            let path = match error.and_then(|e| e.path()) {
+                Some(p) => string_to_mut_c_chars!(p.to_string_lossy().as_ref()),
+                None => std::ptr::null_mut(),
+            };
+            let traceback = match error.and_then(|e| e.traceback()) {
+                Some(t) => string_to_mut_c_chars!(t),
+                None => std::ptr::null_mut(),
+            };
+
+            CRuleRun {
+                id: string_to_mut_c_chars!(r.id()),
+                tags: me.as_mut_ptr(),
+                ntags: me.len(),
+                engine: r.engine(),
+                outcome,
+                reason,
+                duration_us: r.duration().as_micros() as u64,
+                nwarnings: r.warning_count(),
+                path,
+                line: error.and_then(|e| e.line()).unwrap_or(0),
+                traceback,
+            }
+        })
+        .collect();
+    c_runs.shrink_to_fit();
+
+    let mut me = ManuallyDrop::new(c_runs);
+    unsafe {
+        *rules = me.as_mut_ptr();
+    }
+    me.len().try_into().unwrap()
+}
+
+#[no_mangle]
+pub extern "C" fn virt_lint_rules_free(rules: *mut *mut CRuleRun, nrules: *mut isize) {
+    if rules.is_null() || nrules.is_null() || unsafe { *nrules <= 0 } {
+        return;
+    }
+
+    let v = unsafe { Vec::from_raw_parts(*rules, *nrules as usize, *nrules as usize) };
+
+    v.iter().for_each(|r| {
+        let tags = unsafe { Vec::from_raw_parts(r.tags, r.ntags, r.ntags) };
+
+        tags.iter().for_each(|t| virt_lint_string_free(*t));
+        virt_lint_string_free(r.id);
+        virt_lint_string_free(r.reason);
+        virt_lint_string_free(r.path);
+        virt_lint_string_free(r.traceback);
+    });
+
+    unsafe {
+        *rules = std::ptr::null_mut();
+        *nrules = 0;
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone)]
+pub struct CValidatorInfo {
+    id: *mut std::ffi::c_char,
+    engine: ValidatorEngine,
+    path: *mut std::ffi::c_char,
+    tags: *mut *mut std::ffi::c_char,
+    ntags: usize,
+    description: *mut std::ffi::c_char,
+    has_level: bool,
+    level: WarningLevel,
+    has_needs: bool,
+    needs_connection: bool,
+    needs_capabilities: bool,
+    needs_domain_capabilities: bool,
+}
+
+#[no_mangle]
+pub extern "C" fn virt_lint_list_validators(
+    config: *const VirtLintConfig,
+    validators: *mut *mut CValidatorInfo,
+    err: *mut *mut VirtLintError,
+) -> isize {
+    virt_lint_error_free(err);
+
+    check_not_null!(validators, err, -1);
+
+    let default_config = VirtLintConfig::default();
+    let config = if config.is_null() {
+        &default_config
+    } else {
+        unsafe { &*config }
+    };
+
+    let ret = match VirtLint::list_validators(config) {
+        Ok(x) => x,
+        Err(x) => {
+            err_set(err, x);
+            return -1;
+        }
+    };
+
+    let mut c_info: Vec<CValidatorInfo> = ret
+        .iter()
+        .map(|i| {
+            let mut v: Vec<_> = i
+                .tags()
+                .iter()
+                .map(|s| string_to_mut_c_chars!(s.as_str()))
+                .collect();
+            v.shrink_to_fit();
+
+            let mut me = ManuallyDrop::new(v);
+
+            CValidatorInfo {
+                id: string_to_mut_c_chars!(i.id()),
+                engine: i.engine(),
+                path: match i.path() {
+                    Some(p) => string_to_mut_c_chars!(p.to_string_lossy().as_ref()),
+                    None => std::ptr::null_mut(),
+                },
+                tags: me.as_mut_ptr(),
+                ntags: me.len(),
+                description: string_to_mut_c_chars!(i.description()),
+                has_level: i.level().is_some(),
+                level: i.level().unwrap_or(WarningLevel::Notice),
+                has_needs: i.needs_connection().is_some(),
+                needs_connection: i.needs_connection().unwrap_or_default(),
+                needs_capabilities: i.needs_capabilities().unwrap_or_default(),
+                needs_domain_capabilities: i.needs_domain_capabilities().unwrap_or_default(),
+            }
+        })
+        .collect();
+    c_info.shrink_to_fit();
+
+    let mut me = ManuallyDrop::new(c_info);
+    unsafe {
+        *validators = me.as_mut_ptr();
+    }
+    me.len().try_into().unwrap()
+}
+
+#[no_mangle]
+pub extern "C" fn virt_lint_validators_free(
+    validators: *mut *mut CValidatorInfo,
+    nvalidators: *mut isize,
+) {
+    if validators.is_null() || nvalidators.is_null() || unsafe { *nvalidators <= 0 } {
+        return;
+    }
+
+    let v =
+        unsafe { Vec::from_raw_parts(*validators, *nvalidators as usize, *nvalidators as usize) };
+
+    v.iter().for_each(|i| {
+        let tags = unsafe { Vec::from_raw_parts(i.tags, i.ntags, i.ntags) };
+
+        tags.iter().for_each(|t| virt_lint_string_free(*t));
+        virt_lint_string_free(i.id);
+        virt_lint_string_free(i.path);
+        virt_lint_string_free(i.description);
+    });
+
+    unsafe {
+        *validators = std::ptr::null_mut();
+        *nvalidators = 0;
+    }
+}
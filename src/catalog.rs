@@ -0,0 +1,272 @@
+/* SPDX-License-Identifier: LGPL-3.0-or-later */
+
+use crate::*;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Message catalog with translations of warning messages.
+///
+/// Catalogs are gettext `.po` files, one per locale (e.g. `cs.po` or `pt_BR.po`). Each entry is
+/// keyed by the rule ID (`msgctxt`) and the English message template (`msgid`). Entries without
+/// `msgctxt` apply to all rules. Fuzzy and untranslated entries are ignored.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Catalog {
+    messages: HashMap<(Option<String>, String), String>,
+}
+
+#[derive(Default)]
+struct PoEntry {
+    msgctxt: Option<String>,
+    msgid: Option<String>,
+    msgstr: Option<String>,
+    fuzzy: bool,
+}
+
+#[derive(Clone, Copy)]
+enum PoField {
+    Msgctxt,
+    Msgid,
+    Msgstr,
+    Ignored,
+}
+
+fn po_unquote(s: &str, line: usize) -> VirtLintResult<String> {
+    let err = || VirtLintError::CatalogError(format!("line {line}: malformed string"));
+
+    let s = s
+        .trim()
+        .strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .ok_or_else(err)?;
+
+    let mut ret = String::new();
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            ret.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => ret.push('\n'),
+            Some('t') => ret.push('\t'),
+            Some('"') => ret.push('"'),
+            Some('\\') => ret.push('\\'),
+            _ => return Err(err()),
+        }
+    }
+
+    Ok(ret)
+}
+
+impl Catalog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse catalog from `.po` file contents.
+    pub fn from_po(po: &str) -> VirtLintResult<Self> {
+        let mut catalog = Self::new();
+        let mut entry = PoEntry::default();
+        let mut field = PoField::Ignored;
+
+        for (i, line) in po.lines().enumerate() {
+            let line = line.trim();
+            let lineno = i + 1;
+
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(flags) = line.strip_prefix("#,") {
+                // Flags start a new entry
+                catalog.add(std::mem::take(&mut entry));
+                entry.fuzzy = flags.split(',').any(|f| f.trim() == "fuzzy");
+                continue;
+            }
+
+            if line.starts_with('#') {
+                continue;
+            }
+
+            if line.starts_with('"') {
+                let s = po_unquote(line, lineno)?;
+
+                let target = match field {
+                    PoField::Msgctxt => &mut entry.msgctxt,
+                    PoField::Msgid => &mut entry.msgid,
+                    PoField::Msgstr => &mut entry.msgstr,
+                    PoField::Ignored => continue,
+                };
+
+                match target {
+                    Some(x) => x.push_str(&s),
+                    None => {
+                        return Err(VirtLintError::CatalogError(format!(
+                            "line {lineno}: unexpected string"
+                        )))
+                    }
+                }
+                continue;
+            }
+
+            let (keyword, rest) = line.split_once(char::is_whitespace).ok_or_else(|| {
+                VirtLintError::CatalogError(format!("line {lineno}: malformed line"))
+            })?;
+
+            let s = po_unquote(rest, lineno)?;
+
+            field = match keyword {
+                "msgctxt" => {
+                    if entry.msgid.is_some() {
+                        catalog.add(std::mem::take(&mut entry));
+                    }
+                    entry.msgctxt = Some(s);
+                    PoField::Msgctxt
+                }
+                "msgid" => {
+                    if entry.msgid.is_some() {
+                        catalog.add(std::mem::take(&mut entry));
+                    }
+                    entry.msgid = Some(s);
+                    PoField::Msgid
+                }
+                "msgstr" | "msgstr[0]" => {
+                    entry.msgstr = Some(s);
+                    PoField::Msgstr
+                }
+                "msgid_plural" => PoField::Ignored,
+                x if x.starts_with("msgstr[") => PoField::Ignored,
+                x => {
+                    return Err(VirtLintError::CatalogError(format!(
+                        "line {lineno}: unknown keyword '{x}'"
+                    )))
+                }
+            };
+        }
+
+        catalog.add(entry);
+        Ok(catalog)
+    }
+
+    fn add(&mut self, entry: PoEntry) {
+        if entry.fuzzy {
+            return;
+        }
+
+        // The header has an empty msgid
+        match (entry.msgid, entry.msgstr) {
+            (Some(msgid), Some(msgstr)) if !msgid.is_empty() && !msgstr.is_empty() => {
+                self.messages.insert((entry.msgctxt, msgid), msgstr);
+            }
+            _ => {}
+        }
+    }
+
+    /// Load catalog from a `.po` file.
+    pub fn load(path: &Path) -> VirtLintResult<Self> {
+        Self::from_po(&std::fs::read_to_string(path)?)
+    }
+
+    /// Find and load catalog for `locale` (e.g. `cs_CZ.UTF-8`).
+    ///
+    /// If `locale` is `None` then it is obtained from the environment (`LC_ALL`, `LC_MESSAGES`
+    /// and `LANG` variables, in that order). Catalogs are looked up in directories listed in
+    /// `VIRT_LINT_LOCALE_PATH` (or `/usr/share/virt-lint/po` and `./po`), first for the full
+    /// locale name (`cs_CZ.po`) then for the language only (`cs.po`).
+    ///
+    /// Returns `None` for the "C" locale or if there is no catalog for the locale.
+    pub fn find(locale: Option<&str>) -> VirtLintResult<Option<Self>> {
+        let locale = match locale {
+            Some(x) => x.to_string(),
+            None => match locale_from_env() {
+                Some(x) => x,
+                None => return Ok(None),
+            },
+        };
+
+        // Strip encoding and modifier, e.g. cs_CZ.UTF-8@euro
+        let name = locale
+            .split(['.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_string();
+
+        if name.is_empty() || name == "C" || name == "POSIX" {
+            return Ok(None);
+        }
+
+        let mut names = vec![name.clone()];
+        if let Some((lang, _)) = name.split_once('_') {
+            names.push(lang.to_string());
+        }
+
+        let paths: Vec<PathBuf> = match std::env::var_os("VIRT_LINT_LOCALE_PATH") {
+            Some(x) => std::env::split_paths(&x).collect(),
+            None => vec![
+                PathBuf::from("/usr/share/virt-lint/po"),
+                PathBuf::from("./po"),
+            ],
+        };
+
+        for name in names {
+            for path in paths.iter() {
+                let file = path.join(&name).with_extension("po");
+
+                if file.is_file() {
+                    return Ok(Some(Self::load(&file)?));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Translate message template `msgid` of rule `rule`.
+    pub(crate) fn translate(&self, rule: &str, msgid: &str) -> Option<&str> {
+        self.messages
+            .get(&(Some(rule.to_string()), msgid.to_string()))
+            .or_else(|| self.messages.get(&(None, msgid.to_string())))
+            .map(String::as_str)
+    }
+}
+
+fn locale_from_env() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|v| std::env::var(v).ok())
+        .find(|v| !v.is_empty())
+}
+
+/// Render message template, i.e. replace `{name}` with corresponding value from `data`.
+///
+/// Only placeholders naming a key of `data` are replaced, any other text (including braces) is
+/// kept as it is.
+pub(crate) fn render(template: &str, data: &WarningData) -> String {
+    let mut ret = String::new();
+    let mut rest = template;
+
+    while let Some(i) = rest.find('{') {
+        ret.push_str(&rest[..i]);
+        rest = &rest[i..];
+
+        let value = rest[1..]
+            .split_once('}')
+            .and_then(|(name, _)| data.get(name).map(|v| (name.len(), v)));
+
+        match value {
+            Some((len, value)) => {
+                ret.push_str(&value.to_string());
+                rest = &rest[len + 2..];
+            }
+            None => {
+                ret.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    ret.push_str(rest);
+    ret
+}
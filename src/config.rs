@@ -0,0 +1,355 @@
+/* SPDX-License-Identifier: LGPL-3.0-or-later */
+
+use crate::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// System wide configuration file
+const SYSTEM_CONFIG: &str = "/etc/virt-lint/config.toml";
+
+/// Per-user configuration file, relative to `$XDG_CONFIG_HOME` (or `~/.config`)
+const USER_CONFIG: &str = "virt-lint/config.toml";
+
+/// Per-project configuration file, looked up in the current directory
+const PROJECT_CONFIG: &str = ".virt-lint.toml";
+
+/// Override for warnings produced by a rule.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RuleSetting {
+    /// Don't run the rule at all, so it produces no warnings
+    Disabled,
+
+    /// Report warnings produced by the rule with given level
+    Level(WarningLevel),
+}
+
+/// Lint configuration.
+///
+/// Allows overriding the level of warnings produced by selected rules, silencing rules
+/// completely, or filtering out warnings below given level. See [`VirtLint::config_set()`].
+///
+/// Besides that, it holds defaults for applications (connection URI, validator tags, output
+/// format), directories with Lua and Python validators, and parameters of rules. The
+/// configuration can be loaded from TOML files, see [`load_default()`].
+///
+/// [`load_default()`]: VirtLintConfig::load_default
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VirtLintConfig {
+    rules: HashMap<String, RuleSetting>,
+    min_level: Option<WarningLevel>,
+    uri: Option<String>,
+    format: Option<String>,
+    validator_tags: Vec<String>,
+    lua_paths: Vec<PathBuf>,
+    python_paths: Vec<PathBuf>,
+    params: HashMap<String, WarningData>,
+    keep_going: Option<bool>,
+}
+
+/// The `[validators]` table of a configuration file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ConfigFileValidators {
+    enabled: Vec<String>,
+    disabled: Vec<String>,
+    lua_path: Vec<PathBuf>,
+    python_path: Vec<PathBuf>,
+}
+
+/// A configuration file, see [`VirtLintConfig::from_toml()`].
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ConfigFile {
+    uri: Option<String>,
+    format: Option<String>,
+    min_level: Option<String>,
+    keep_going: Option<bool>,
+    validators: ConfigFileValidators,
+    rules: HashMap<String, String>,
+    params: HashMap<String, WarningData>,
+}
+
+impl VirtLintConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set override for a rule.
+    ///
+    /// The `rule` is either a rule ID (see [`VirtLintWarning::id()`]) or a validator tag. If
+    /// settings exist for both the rule ID and some of its tags, then the rule ID wins. Out of
+    /// several tags, the longest (i.e. the most specific) one wins.
+    pub fn rule_set(&mut self, rule: &str, setting: RuleSetting) {
+        self.rules.insert(rule.to_string(), setting);
+    }
+
+    /// Remove override for a rule.
+    pub fn rule_unset(&mut self, rule: &str) {
+        self.rules.remove(rule);
+    }
+
+    /// Get override for a rule (as set by [`rule_set()`]).
+    ///
+    /// [`rule_set()`]: VirtLintConfig::rule_set
+    pub fn rule_get(&self, rule: &str) -> Option<RuleSetting> {
+        self.rules.get(rule).copied()
+    }
+
+    /// Set minimal level of reported warnings.
+    ///
+    /// Warnings less severe than `level` are dropped, e.g. with [`WarningLevel::Warning`] only
+    /// errors and warnings are reported. Pass `None` to report all warnings.
+    pub fn min_level_set(&mut self, level: Option<WarningLevel>) {
+        self.min_level = level;
+    }
+
+    /// Get minimal level of reported warnings.
+    pub fn min_level(&self) -> Option<WarningLevel> {
+        self.min_level
+    }
+
+    /// Set default connection URI.
+    pub fn uri_set(&mut self, uri: Option<String>) {
+        self.uri = uri;
+    }
+
+    /// Get default connection URI.
+    pub fn uri(&self) -> Option<&str> {
+        self.uri.as_deref()
+    }
+
+    /// Set default output format. It is up to applications to interpret it.
+    pub fn format_set(&mut self, format: Option<String>) {
+        self.format = format;
+    }
+
+    /// Get default output format.
+    pub fn format(&self) -> Option<&str> {
+        self.format.as_deref()
+    }
+
+    /// Set validator tags to run by default. Empty means all.
+    pub fn validator_tags_set(&mut self, tags: Vec<String>) {
+        self.validator_tags = tags;
+    }
+
+    /// Get validator tags to run by default.
+    pub fn validator_tags(&self) -> &[String] {
+        &self.validator_tags
+    }
+
+    /// Set directories to look for Lua validators in.
+    ///
+    /// If empty, `VIRT_LINT_LUA_PATH` environment variable or the built-in default is used.
+    pub fn lua_paths_set(&mut self, paths: Vec<PathBuf>) {
+        self.lua_paths = paths;
+    }
+
+    /// Get directories to look for Lua validators in.
+    pub fn lua_paths(&self) -> &[PathBuf] {
+        &self.lua_paths
+    }
+
+    /// Set directories to look for Python validators in.
+    ///
+    /// If empty, `VIRT_LINT_PYTHON_PATH` environment variable or the built-in default is used.
+    pub fn python_paths_set(&mut self, paths: Vec<PathBuf>) {
+        self.python_paths = paths;
+    }
+
+    /// Get directories to look for Python validators in.
+    pub fn python_paths(&self) -> &[PathBuf] {
+        &self.python_paths
+    }
+
+    /// Set parameter `name` of rule `rule` (a rule ID).
+    ///
+    /// Parameters tune what a rule checks, e.g. a threshold. Lua and Python validators obtain
+    /// them via `vl:param()` and `vl.param()` respectively.
+    pub fn rule_param_set(&mut self, rule: &str, name: &str, value: WarningValue) {
+        self.params
+            .entry(rule.to_string())
+            .or_default()
+            .insert(name.to_string(), value);
+    }
+
+    /// Get parameter `name` of rule `rule`.
+    pub fn rule_param(&self, rule: &str, name: &str) -> Option<&WarningValue> {
+        self.params.get(rule).and_then(|p| p.get(name))
+    }
+
+    /// Get all parameters of rule `rule`.
+    pub fn rule_params(&self, rule: &str) -> Option<&WarningData> {
+        self.params.get(rule)
+    }
+
+    /// Set whether validation goes on when a rule fails.
+    ///
+    /// By default, an error in a rule (e.g. a bug in a Lua or Python validator, or a panic in a
+    /// built-in one) makes [`VirtLint::validate()`] fail and remaining rules are not run. With
+    /// `keep_going` the error is only recorded (see [`RuleRun::failed()`]) and validation
+    /// continues with other rules.
+    pub fn keep_going_set(&mut self, keep_going: bool) {
+        self.keep_going = Some(keep_going);
+    }
+
+    /// Get whether validation goes on when a rule fails.
+    pub fn keep_going(&self) -> bool {
+        self.keep_going.unwrap_or(false)
+    }
+
+    /// Parse configuration from TOML.
+    ///
+    /// ```toml
+    /// uri = "qemu:///system"
+    /// format = "pretty"
+    /// min_level = "notice"
+    /// keep_going = true
+    ///
+    /// [validators]
+    /// enabled = ["TAG_1", "TAG_2"]
+    /// disabled = ["TAG_3"]
+    /// lua_path = ["/srv/virt-lint/validators_lua"]
+    /// python_path = ["/srv/virt-lint/validators_python"]
+    ///
+    /// [rules]
+    /// "numa.fit" = "warning"
+    /// "pcie.root-ports.free" = "disabled"
+    ///
+    /// [params."common/check_numa"]
+    /// margin_kib = 1048576
+    /// ```
+    ///
+    /// Disabled tags are equivalent to [`RuleSetting::Disabled`] in the `rules` table.
+    pub fn from_toml(toml: &str) -> VirtLintResult<Self> {
+        let file: ConfigFile = toml::from_str(toml)?;
+        let mut config = Self::new();
+
+        config.uri = file.uri;
+        config.format = file.format;
+        config.min_level = file.min_level.as_deref().map(str::parse).transpose()?;
+        config.keep_going = file.keep_going;
+        config.validator_tags = file.validators.enabled;
+        config.lua_paths = file.validators.lua_path;
+        config.python_paths = file.validators.python_path;
+        config.params = file.params;
+
+        for tag in file.validators.disabled {
+            config.rule_set(&tag, RuleSetting::Disabled);
+        }
+
+        for (rule, setting) in file.rules {
+            let setting = match setting.as_str() {
+                "disabled" => RuleSetting::Disabled,
+                level => RuleSetting::Level(level.parse()?),
+            };
+            config.rule_set(&rule, setting);
+        }
+
+        Ok(config)
+    }
+
+    /// Load configuration from a TOML file, see [`from_toml()`].
+    ///
+    /// [`from_toml()`]: VirtLintConfig::from_toml
+    pub fn load(path: &Path) -> VirtLintResult<Self> {
+        let toml = std::fs::read_to_string(path)
+            .map_err(|e| VirtLintError::IOError(format!("{}: {e}", path.display())))?;
+        Self::from_toml(&toml)
+    }
+
+    /// Merge `other` into the configuration. Settings present in `other` take precedence.
+    pub fn merge(&mut self, other: Self) {
+        self.rules.extend(other.rules);
+        self.min_level = other.min_level.or(self.min_level);
+        self.keep_going = other.keep_going.or(self.keep_going);
+        self.uri = other.uri.or(self.uri.take());
+        self.format = other.format.or(self.format.take());
+
+        if !other.validator_tags.is_empty() {
+            self.validator_tags = other.validator_tags;
+        }
+        if !other.lua_paths.is_empty() {
+            self.lua_paths = other.lua_paths;
+        }
+        if !other.python_paths.is_empty() {
+            self.python_paths = other.python_paths;
+        }
+
+        for (rule, params) in other.params {
+            self.params.entry(rule).or_default().extend(params);
+        }
+    }
+
+    /// Locations of configuration files, from the lowest precedence to the highest one:
+    ///
+    /// 1. system: `/etc/virt-lint/config.toml`,
+    /// 2. user: `$XDG_CONFIG_HOME/virt-lint/config.toml` (or `~/.config/virt-lint/config.toml`),
+    /// 3. project: `.virt-lint.toml` in the current directory.
+    pub fn default_paths() -> Vec<PathBuf> {
+        let mut paths = vec![PathBuf::from(SYSTEM_CONFIG)];
+
+        if let Some(dir) = std::env::var_os("XDG_CONFIG_HOME").filter(|d| !d.is_empty()) {
+            paths.push(PathBuf::from(dir).join(USER_CONFIG));
+        } else if let Some(home) = std::env::var_os("HOME").filter(|d| !d.is_empty()) {
+            paths.push(PathBuf::from(home).join(".config").join(USER_CONFIG));
+        }
+
+        paths.push(PathBuf::from(PROJECT_CONFIG));
+        paths
+    }
+
+    /// Load and merge all configuration files that exist, see [`default_paths()`].
+    ///
+    /// [`default_paths()`]: VirtLintConfig::default_paths
+    pub fn load_default() -> VirtLintResult<Self> {
+        let mut config = Self::new();
+
+        for path in Self::default_paths() {
+            if path.exists() {
+                config.merge(Self::load(&path)?);
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Setting of rule `id` with `tags`. Rule ID wins over tags, the longest tag over shorter
+    /// ones.
+    fn setting(&self, id: &str, tags: &[String]) -> Option<&RuleSetting> {
+        self.rules.get(id).or_else(|| {
+            tags.iter()
+                .filter(|t| self.rules.contains_key(*t))
+                .max_by_key(|t| t.len())
+                .and_then(|t| self.rules.get(t))
+        })
+    }
+
+    /// Whether rule `id` with `tags` is disabled, and thus not run at all.
+    pub(crate) fn disabled(&self, id: &str, tags: &[String]) -> bool {
+        matches!(self.setting(id, tags), Some(RuleSetting::Disabled))
+    }
+
+    /// Apply the configuration onto a warning.
+    ///
+    /// Returns `None` if the warning is to be dropped.
+    pub(crate) fn apply(&self, w: VirtLintWarning) -> Option<VirtLintWarning> {
+        let mut w = w;
+
+        match self.setting(&w.id, &w.tags) {
+            Some(RuleSetting::Disabled) => return None,
+            Some(RuleSetting::Level(level)) => w.level = *level,
+            None => {}
+        }
+
+        if let Some(min_level) = self.min_level {
+            // Levels are ordered from the most severe one
+            if w.level > min_level {
+                return None;
+            }
+        }
+
+        Some(w)
+    }
+}
@@ -0,0 +1,190 @@
+/* SPDX-License-Identifier: LGPL-3.0-or-later */
+
+//! Documentation of linting rules.
+//!
+//! Built-in rules carry their documentation in the source, Lua and Python validators in a
+//! header comment (or a docstring). Either way, the text has the following format:
+//!
+//! ```text
+//! One line summary
+//!
+//! Longer description, possibly spanning
+//! several paragraphs.
+//!
+//! Rationale: Why the rule matters.
+//!
+//! Level: error
+//! Needs: connection, capabilities, domain-capabilities
+//!
+//! References:
+//! - https://libvirt.org/formatdomain.html
+//! ```
+//!
+//! Everything but the summary is optional. The `Level:` and `Needs:` lines declare the level of
+//! warnings the rule produces and what it needs besides domain XML (or `none`). Lua and Python
+//! validators should declare both as they are not known otherwise.
+
+use crate::WarningLevel;
+use serde::Serialize;
+
+const RATIONALE: &str = "Rationale:";
+const REFERENCES: &str = "References:";
+const LEVEL: &str = "Level:";
+const NEEDS: &str = "Needs:";
+
+/// Documentation of a linting rule, see [`VirtLint::rule_docs()`].
+///
+/// [`VirtLint::rule_docs()`]: crate::VirtLint::rule_docs
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct RuleDoc {
+    id: String,
+    tags: Vec<String>,
+    summary: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rationale: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    references: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    level: Option<WarningLevel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    needs: Option<Vec<String>>,
+}
+
+impl RuleDoc {
+    /// Parse documentation `text` of rule `id`.
+    pub(crate) fn parse(id: &str, tags: Vec<String>, text: &str) -> Self {
+        let mut ret = Self {
+            id: id.to_string(),
+            tags,
+            ..Default::default()
+        };
+        let mut description: Vec<String> = Vec::new();
+
+        for paragraph in text.trim().split("\n\n") {
+            let lines: Vec<&str> = paragraph.lines().map(|l| l.trim()).collect();
+            let joined = lines.join(" ");
+
+            if ret.summary.is_empty() {
+                ret.summary = joined;
+            } else if let Some(rationale) = joined.strip_prefix(RATIONALE) {
+                ret.rationale = Some(rationale.trim().to_string());
+            } else if lines
+                .iter()
+                .all(|l| l.starts_with(LEVEL) || l.starts_with(NEEDS))
+            {
+                for line in lines {
+                    if let Some(level) = line.strip_prefix(LEVEL) {
+                        ret.level = level.trim().parse().ok();
+                    } else if let Some(needs) = line.strip_prefix(NEEDS) {
+                        ret.needs = Some(
+                            needs
+                                .split(',')
+                                .map(str::trim)
+                                .filter(|n| !n.is_empty() && *n != "none")
+                                .map(String::from)
+                                .collect(),
+                        );
+                    }
+                }
+            } else if lines.first() == Some(&REFERENCES) {
+                ret.references.extend(
+                    lines[1..]
+                        .iter()
+                        .map(|l| l.trim_start_matches('-').trim().to_string())
+                        .filter(|l| !l.is_empty()),
+                );
+            } else if !joined.is_empty() {
+                description.push(joined);
+            }
+        }
+
+        ret.description = description.join("\n\n");
+        ret
+    }
+
+    /// Rule ID, see [`VirtLintWarning::id()`].
+    ///
+    /// [`VirtLintWarning::id()`]: crate::VirtLintWarning::id
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Tags of the rule.
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    /// One line summary of what the rule checks. Empty if the rule is not documented.
+    pub fn summary(&self) -> &str {
+        &self.summary
+    }
+
+    /// Longer description of the rule. Paragraphs are separated by an empty line.
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Why the rule matters.
+    pub fn rationale(&self) -> Option<&str> {
+        self.rationale.as_deref()
+    }
+
+    /// Links to further documentation.
+    pub fn references(&self) -> &[String] {
+        &self.references
+    }
+
+    /// Level of warnings the rule produces, as declared by `Level:`.
+    pub fn level(&self) -> Option<WarningLevel> {
+        self.level
+    }
+
+    /// What the rule needs besides domain XML (`connection`, `capabilities` and
+    /// `domain-capabilities`), as declared by `Needs:`. Empty if it needs nothing, `None` if
+    /// not declared.
+    pub fn needs(&self) -> Option<&[String]> {
+        self.needs.as_deref()
+    }
+}
+
+/// Extract the header comment from `code`, i.e. leading lines starting with `comment`.
+pub(crate) fn header_comment(code: &str, comment: &str) -> String {
+    code.lines()
+        .map(|l| l.trim_start())
+        .take_while(|l| l.starts_with(comment))
+        .map(|l| {
+            l[comment.len()..]
+                .strip_prefix(' ')
+                .unwrap_or(&l[comment.len()..])
+        })
+        .collect::<Vec<&str>>()
+        .join("\n")
+}
+
+/// Extract the module docstring from Python `code`, or its header comment if there is none.
+pub(crate) fn python_docstring(code: &str) -> String {
+    // Skip the shebang, encoding declarations and so on
+    let start = code
+        .lines()
+        .map(|l| l.trim())
+        .find(|l| !l.is_empty() && !l.starts_with('#'));
+
+    let quote = match start {
+        Some(l) if l.starts_with("\"\"\"") => "\"\"\"",
+        Some(l) if l.starts_with("'''") => "'''",
+        _ => return header_comment(code, "#"),
+    };
+
+    let begin = code.find(quote).unwrap_or_default() + quote.len();
+
+    match code[begin..].find(quote) {
+        Some(end) => code[begin..begin + end]
+            .lines()
+            .map(|l| l.trim())
+            .collect::<Vec<&str>>()
+            .join("\n"),
+        None => String::new(),
+    }
+}
@@ -15,9 +15,18 @@ pub enum VirtLintError {
     #[error("Unknown validator tag: {0}")]
     UnknownValidatorTag(String),
 
+    #[error("Rule ID implemented by more validators: {0}")]
+    DuplicateRuleId(String),
+
+    #[error("Invalid validator tag expression {0}")]
+    TagExpression(String),
+
     #[error("Invalid argument: {0}")]
     InvalidArgument(&'static str),
 
+    #[error("Unable to edit XML: {0}")]
+    XMLEdit(String),
+
     #[error("I/O error: {0}")]
     IOError(String),
 
@@ -30,6 +39,18 @@ pub enum VirtLintError {
     #[error("Python error: {0}")]
     PythonError(#[from] pyo3::PyErr),
 
+    #[error("Unable to parse message catalog: {0}")]
+    CatalogError(String),
+
+    #[error("JSON error: {0}")]
+    JSONError(#[from] serde_json::Error),
+
+    #[error("Unable to parse configuration: {0}")]
+    TOMLError(#[from] toml::de::Error),
+
+    #[error("Panic: {0}")]
+    Panic(String),
+
     #[error(transparent)]
     ParseIntError(#[from] std::num::ParseIntError),
 }
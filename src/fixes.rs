@@ -0,0 +1,147 @@
+/* SPDX-License-Identifier: LGPL-3.0-or-later */
+
+use crate::*;
+use libxml::parser::Parser;
+use libxml::tree::{Document, Node, NodeType, SaveOptions};
+use libxml::xpath::Context;
+
+fn is_blank(node: &Node) -> bool {
+    node.get_type() == Some(NodeType::TextNode) && node.get_content().trim().is_empty()
+}
+
+fn find_nodes(doc: &Document, xpath: &str) -> VirtLintResult<Vec<Node>> {
+    let ctxt = Context::new(doc).unwrap();
+
+    let nodes = ctxt
+        .evaluate(xpath)
+        .map_err(|_| VirtLintError::XMLEdit(format!("invalid XPath '{xpath}'")))?
+        .get_nodes_as_vec();
+
+    if nodes.is_empty() {
+        return Err(VirtLintError::XMLEdit(format!(
+            "XPath '{xpath}' matched no nodes"
+        )));
+    }
+
+    Ok(nodes)
+}
+
+/// Parse `xml` and import its root element into `doc`.
+fn import_fragment(doc: &mut Document, xml: &str) -> VirtLintResult<Node> {
+    let parser = Parser::default();
+    let frag = parser.parse_string(xml)?;
+
+    let mut root = frag
+        .get_root_element()
+        .ok_or_else(|| VirtLintError::XMLEdit(format!("no element in '{xml}'")))?;
+
+    root.unlink();
+
+    doc.import_node(&mut root)
+        .map_err(|_| VirtLintError::XMLEdit(format!("unable to import '{xml}'")))
+}
+
+fn insert(doc: &mut Document, xpath: &str, xml: &str) -> VirtLintResult<()> {
+    for mut parent in find_nodes(doc, xpath)? {
+        let mut node = import_fragment(doc, xml)?;
+
+        // Try to keep the indentation. If the last child is a blank text node then it holds
+        // indentation of the closing tag of parent. Children are indented by two more spaces.
+        match parent.get_last_child() {
+            Some(mut last) if is_blank(&last) => {
+                let content = last.get_content();
+                let indent = content.rsplit('\n').next().unwrap_or_default();
+                let mut blank = Node::new_text(&format!("\n{indent}  "), doc)
+                    .map_err(|_| VirtLintError::XMLEdit(String::from("unable to create text")))?;
+
+                last.add_prev_sibling(&mut blank)
+                    .map_err(|x| VirtLintError::XMLEdit(x.to_string()))?;
+                last.add_prev_sibling(&mut node)
+                    .map_err(|x| VirtLintError::XMLEdit(x.to_string()))?;
+            }
+            _ => {
+                parent
+                    .add_child(&mut node)
+                    .map_err(VirtLintError::XMLEdit)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn replace(doc: &mut Document, xpath: &str, xml: &str) -> VirtLintResult<()> {
+    for mut old in find_nodes(doc, xpath)? {
+        match old.get_type() {
+            Some(NodeType::AttributeNode) => {
+                old.set_content(xml)
+                    .map_err(|x| VirtLintError::XMLEdit(x.to_string()))?;
+            }
+            _ => {
+                let mut node = import_fragment(doc, xml)?;
+
+                old.add_next_sibling(&mut node)
+                    .map_err(|x| VirtLintError::XMLEdit(x.to_string()))?;
+                old.unlink();
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn remove(doc: &mut Document, xpath: &str) -> VirtLintResult<()> {
+    for mut old in find_nodes(doc, xpath)? {
+        match old.get_type() {
+            Some(NodeType::AttributeNode) => {
+                let name = old.get_name();
+
+                if let Some(mut parent) = old.get_parent() {
+                    drop(old);
+                    parent
+                        .remove_property(&name)
+                        .map_err(|x| VirtLintError::XMLEdit(x.to_string()))?;
+                }
+            }
+            _ => {
+                // Remove indentation too, so that no empty line is left behind
+                if let Some(mut prev) = old.get_prev_sibling() {
+                    if is_blank(&prev) {
+                        prev.unlink();
+                    }
+                }
+
+                old.unlink();
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub(crate) fn apply_fixes(domxml: &str, fixes: &[&WarningFix]) -> VirtLintResult<String> {
+    let parser = Parser::default();
+    let mut doc = parser.parse_string(domxml)?;
+
+    for fix in fixes {
+        for edit in fix.edits() {
+            match edit {
+                XmlEdit::Insert { xpath, xml } => insert(&mut doc, xpath, xml)?,
+                XmlEdit::Replace { xpath, xml } => replace(&mut doc, xpath, xml)?,
+                XmlEdit::Remove { xpath } => remove(&mut doc, xpath)?,
+            }
+        }
+    }
+
+    let options = SaveOptions {
+        no_declaration: !domxml.trim_start().starts_with("<?xml"),
+        ..Default::default()
+    };
+
+    let mut ret = doc.to_string_with_options(options).trim_end().to_string();
+    if domxml.ends_with('\n') {
+        ret.push('\n');
+    }
+
+    Ok(ret)
+}
@@ -1,4 +1,8 @@
 use crate::*;
+use mlua::{FromLua, IntoLua, Lua, Value};
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyList, PyTuple};
+use std::collections::HashMap;
 
 impl TryFrom<i32> for WarningDomain {
     type Error = VirtLintError;
@@ -32,3 +36,166 @@ impl TryFrom<i32> for WarningLevel {
         Ok(ret)
     }
 }
+
+impl std::str::FromStr for WarningLevel {
+    type Err = VirtLintError;
+
+    fn from_str(s: &str) -> Result<Self, VirtLintError> {
+        let ret = match s.to_lowercase().as_str() {
+            "error" => WarningLevel::Error,
+            "warning" => WarningLevel::Warning,
+            "notice" => WarningLevel::Notice,
+            _ => {
+                return Err(VirtLintError::InvalidArgument("Unknown warning level"));
+            }
+        };
+
+        Ok(ret)
+    }
+}
+
+impl TryFrom<HashMap<String, String>> for XmlEdit {
+    type Error = VirtLintError;
+
+    fn try_from(mut value: HashMap<String, String>) -> Result<Self, VirtLintError> {
+        let xpath = value
+            .remove("xpath")
+            .ok_or(VirtLintError::InvalidArgument("XML edit without xpath"))?;
+
+        let op = value.remove("op").unwrap_or_default();
+        let ret = match op.as_str() {
+            "insert" | "replace" => {
+                let xml = value
+                    .remove("xml")
+                    .ok_or(VirtLintError::InvalidArgument("XML edit without xml"))?;
+
+                if op == "insert" {
+                    XmlEdit::Insert { xpath, xml }
+                } else {
+                    XmlEdit::Replace { xpath, xml }
+                }
+            }
+            "remove" => XmlEdit::Remove { xpath },
+            _ => {
+                return Err(VirtLintError::InvalidArgument("Unknown XML edit operation"));
+            }
+        };
+
+        Ok(ret)
+    }
+}
+
+impl From<bool> for WarningValue {
+    fn from(value: bool) -> Self {
+        WarningValue::Bool(value)
+    }
+}
+
+impl From<i64> for WarningValue {
+    fn from(value: i64) -> Self {
+        WarningValue::Int(value)
+    }
+}
+
+impl From<u64> for WarningValue {
+    fn from(value: u64) -> Self {
+        WarningValue::Int(value.try_into().unwrap_or(i64::MAX))
+    }
+}
+
+impl From<&str> for WarningValue {
+    fn from(value: &str) -> Self {
+        WarningValue::String(value.to_string())
+    }
+}
+
+impl From<String> for WarningValue {
+    fn from(value: String) -> Self {
+        WarningValue::String(value)
+    }
+}
+
+impl<T: Into<WarningValue>> From<Vec<T>> for WarningValue {
+    fn from(value: Vec<T>) -> Self {
+        WarningValue::List(value.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<'lua> FromLua<'lua> for WarningValue {
+    fn from_lua(value: Value<'lua>, _: &'lua Lua) -> mlua::Result<Self> {
+        let ret = match value {
+            Value::Boolean(x) => WarningValue::Bool(x),
+            Value::Integer(x) => WarningValue::Int(x),
+            Value::Number(x) if x.fract() == 0.0 => WarningValue::Int(x as i64),
+            Value::String(x) => WarningValue::String(x.to_str()?.to_string()),
+            Value::Table(x) => WarningValue::List(
+                x.sequence_values::<WarningValue>()
+                    .collect::<mlua::Result<Vec<_>>>()?,
+            ),
+            _ => {
+                return Err(mlua::Error::FromLuaConversionError {
+                    from: value.type_name(),
+                    to: "WarningValue",
+                    message: Some(String::from(
+                        "expected boolean, integer, string or sequence",
+                    )),
+                })
+            }
+        };
+
+        Ok(ret)
+    }
+}
+
+impl<'lua> IntoLua<'lua> for WarningValue {
+    fn into_lua(self, lua: &'lua Lua) -> mlua::Result<Value<'lua>> {
+        match self {
+            WarningValue::Bool(x) => x.into_lua(lua),
+            WarningValue::Int(x) => x.into_lua(lua),
+            WarningValue::String(x) => x.into_lua(lua),
+            WarningValue::List(x) => Ok(Value::Table(lua.create_sequence_from(x)?)),
+        }
+    }
+}
+
+impl<'py> FromPyObject<'py> for WarningValue {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        // Check bool first, as it is a subclass of int in Python
+        if ob.is_instance_of::<PyBool>() {
+            return Ok(WarningValue::Bool(ob.extract()?));
+        }
+
+        if let Ok(x) = ob.extract::<i64>() {
+            return Ok(WarningValue::Int(x));
+        }
+
+        if let Ok(x) = ob.extract::<String>() {
+            return Ok(WarningValue::String(x));
+        }
+
+        if ob.is_instance_of::<PyList>() || ob.is_instance_of::<PyTuple>() {
+            return Ok(WarningValue::List(ob.extract()?));
+        }
+
+        Err(pyo3::exceptions::PyTypeError::new_err(
+            "expected bool, int, str, list or tuple",
+        ))
+    }
+}
+
+impl ToPyObject for WarningValue {
+    fn to_object(&self, py: Python<'_>) -> PyObject {
+        match self {
+            WarningValue::Bool(x) => x.to_object(py),
+            WarningValue::Int(x) => x.to_object(py),
+            WarningValue::String(x) => x.to_object(py),
+            WarningValue::List(x) => x.to_object(py),
+        }
+    }
+}
+
+impl IntoPy<PyObject> for WarningValue {
+    fn into_py(self, py: Python<'_>) -> PyObject {
+        self.to_object(py)
+    }
+}
@@ -0,0 +1,442 @@
+/* SPDX-License-Identifier: LGPL-3.0-or-later */
+
+use crate::*;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// The input of a validation run, see [`LintReport::input()`].
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct LintInput {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    domain_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    domain_uuid: Option<String>,
+    validator_tags: Vec<String>,
+}
+
+impl LintInput {
+    pub(crate) fn new(domxml: &str, validator_tags: &[String]) -> VirtLintResult<Self> {
+        let (domain_name, domain_uuid) = domain_identity(domxml)?;
+
+        Ok(Self {
+            source: None,
+            domain_name,
+            domain_uuid,
+            validator_tags: validator_tags.to_vec(),
+        })
+    }
+
+    /// Where the domain XML came from (e.g. a file name), if set by [`LintReport::source_set()`].
+    pub fn source(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+
+    /// Name of the validated domain.
+    pub fn domain_name(&self) -> Option<&str> {
+        self.domain_name.as_deref()
+    }
+
+    /// UUID of the validated domain.
+    pub fn domain_uuid(&self) -> Option<&str> {
+        self.domain_uuid.as_deref()
+    }
+
+    /// Validator tags the validation was limited to. Empty means all rules were run.
+    pub fn validator_tags(&self) -> &[String] {
+        &self.validator_tags
+    }
+}
+
+/// Outcome of a linting rule, see [`RuleRun::outcome()`].
+#[derive(Clone, Debug, PartialEq, PartialOrd, Ord, Eq, Serialize)]
+#[serde(tag = "outcome", content = "reason", rename_all = "lowercase")]
+pub enum RuleOutcome {
+    /// The rule was run (regardless of whether it produced any warnings)
+    Ran,
+
+    /// The rule could not check anything because it needed information about the host (e.g.
+    /// capabilities) that was not available, typically in the offline mode. A skipped rule
+    /// produces no warnings
+    Skipped(String),
+
+    /// The rule failed with an error
+    Failed(RuleError),
+}
+
+/// Why a linting rule failed, see [`RuleOutcome::Failed`].
+///
+/// For Lua and Python validators this is typically a bug in the script, hence the location and
+/// traceback are recorded too.
+#[derive(Clone, Debug, PartialEq, PartialOrd, Ord, Eq, Serialize)]
+pub struct RuleError {
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    line: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    traceback: Option<String>,
+}
+
+/// Find line number in a Lua or Python error message, i.e. `N` in `check.lua:N:` or
+/// `"check.py", line N`. The last occurrence wins, as it is the innermost frame in Python.
+fn error_line(text: &str, file_name: &str, lua: bool) -> Option<u32> {
+    let pattern = if lua {
+        format!("{file_name}:")
+    } else {
+        format!("{file_name}\", line ")
+    };
+
+    let mut ret = None;
+    for (i, _) in text.match_indices(&pattern) {
+        let digits: String = text[i + pattern.len()..]
+            .chars()
+            .take_while(char::is_ascii_digit)
+            .collect();
+
+        if let Ok(line) = digits.parse() {
+            ret = Some(line);
+            if lua {
+                break;
+            }
+        }
+    }
+
+    ret
+}
+
+/// Format Python exception `err` the way the interpreter would, including the traceback.
+fn python_traceback(err: &pyo3::PyErr) -> Option<String> {
+    use pyo3::prelude::*;
+
+    Python::with_gil(|py| {
+        let lines = py
+            .import_bound("traceback")?
+            .call_method1(
+                "format_exception",
+                (
+                    err.get_type_bound(py),
+                    err.value_bound(py),
+                    err.traceback_bound(py),
+                ),
+            )?
+            .extract::<Vec<String>>()?;
+
+        Ok::<String, PyErr>(lines.concat())
+    })
+    .ok()
+}
+
+impl RuleError {
+    /// Describe `error` of a rule implemented by script at `path` (if any).
+    pub(crate) fn new(error: &VirtLintError, path: Option<&Path>) -> Self {
+        let mut ret = Self {
+            message: error.to_string(),
+            path: path.map(Path::to_path_buf),
+            line: None,
+            traceback: None,
+        };
+
+        match error {
+            VirtLintError::LuaError(mlua::Error::CallbackError { cause, traceback }) => {
+                let mut cause = cause;
+                while let mlua::Error::CallbackError { cause: inner, .. } = cause.as_ref() {
+                    cause = inner;
+                }
+                ret.message = format!("Lua error: {cause}");
+                ret.traceback = Some(traceback.to_string());
+            }
+            VirtLintError::LuaError(_) => {
+                if let Some((message, traceback)) = ret.message.split_once("\nstack traceback:") {
+                    ret.traceback = Some(format!("stack traceback:{traceback}"));
+                    ret.message = message.to_string();
+                }
+            }
+            VirtLintError::PythonError(e) => ret.traceback = python_traceback(e),
+            _ => {}
+        }
+
+        let file_name = path
+            .and_then(Path::file_name)
+            .map(|f| f.to_string_lossy().to_string());
+
+        if let Some(file_name) = file_name {
+            let lua = matches!(error, VirtLintError::LuaError(_));
+            let text = format!(
+                "{}\n{}",
+                ret.message,
+                ret.traceback.as_deref().unwrap_or("")
+            );
+            ret.line = error_line(&text, &file_name, lua);
+        }
+
+        ret
+    }
+
+    /// The error message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Path to the Lua or Python script, `None` for built-in rules.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Line of the script the error occurred at, if known.
+    pub fn line(&self) -> Option<u32> {
+        self.line
+    }
+
+    /// Lua stack traceback or Python traceback, if any.
+    pub fn traceback(&self) -> Option<&str> {
+        self.traceback.as_deref()
+    }
+}
+
+/// A linting rule executed during validation, see [`VirtLint::rules()`].
+#[derive(Clone, Debug, PartialEq, PartialOrd, Ord, Eq, Serialize)]
+pub struct RuleRun {
+    id: String,
+    tags: Vec<String>,
+    engine: ValidatorEngine,
+    #[serde(flatten)]
+    outcome: RuleOutcome,
+    duration_us: u64,
+    warning_count: usize,
+    #[serde(skip)]
+    started: Option<Instant>,
+    #[serde(skip)]
+    no_connect: bool,
+}
+
+impl RuleRun {
+    pub(crate) fn new(id: &str, tags: Vec<String>, engine: ValidatorEngine) -> Self {
+        Self {
+            id: id.to_string(),
+            tags,
+            engine,
+            outcome: RuleOutcome::Ran,
+            duration_us: 0,
+            warning_count: 0,
+            started: Some(Instant::now()),
+            no_connect: false,
+        }
+    }
+
+    /// A rule which emits a warning did check something, even if some information was not
+    /// available (see [`skip()`]). The warning is counted only once it is reported, see
+    /// [`warning_count_set()`].
+    ///
+    /// [`skip()`]: RuleRun::skip
+    /// [`warning_count_set()`]: RuleRun::warning_count_set
+    pub(crate) fn warning_added(&mut self) {
+        self.unskip();
+    }
+
+    pub(crate) fn warning_count_set(&mut self, count: usize) {
+        self.warning_count = count;
+    }
+
+    pub(crate) fn finish(&mut self) {
+        if let Some(started) = self.started.take() {
+            self.duration_us = started.elapsed().as_micros() as u64;
+        }
+    }
+
+    pub(crate) fn skip(&mut self, reason: &str) {
+        if self.outcome == RuleOutcome::Ran {
+            self.outcome = RuleOutcome::Skipped(reason.to_string());
+        }
+    }
+
+    pub(crate) fn unskip(&mut self) {
+        if let RuleOutcome::Skipped(_) = self.outcome {
+            self.outcome = RuleOutcome::Ran;
+        }
+    }
+
+    pub(crate) fn fail(&mut self, error: RuleError) {
+        self.outcome = RuleOutcome::Failed(error);
+    }
+
+    /// The rule needed a connection, but there was none and validation was asked to error out in
+    /// that case.
+    pub(crate) fn set_no_connect(&mut self) {
+        self.no_connect = true;
+    }
+
+    pub(crate) fn no_connect(&self) -> bool {
+        self.no_connect
+    }
+
+    /// Rule ID, see [`VirtLintWarning::id()`].
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Tags of the rule.
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    /// How the rule is implemented.
+    pub fn engine(&self) -> ValidatorEngine {
+        self.engine
+    }
+
+    /// How long the rule took to execute (wall-clock time), including e.g. starting the Python
+    /// interpreter.
+    pub fn duration(&self) -> Duration {
+        Duration::from_micros(self.duration_us)
+    }
+
+    /// Number of warnings of the rule that are reported (see [`VirtLint::warnings()`]), i.e.
+    /// not counting those dropped by configuration, suppressed in domain XML or recorded in
+    /// baseline.
+    pub fn warning_count(&self) -> usize {
+        self.warning_count
+    }
+
+    /// Whether the rule ran, was skipped or failed.
+    pub fn outcome(&self) -> &RuleOutcome {
+        &self.outcome
+    }
+
+    /// Why the rule was skipped, if it was. See [`RuleOutcome::Skipped`].
+    pub fn skipped(&self) -> Option<&str> {
+        match &self.outcome {
+            RuleOutcome::Skipped(reason) => Some(reason),
+            _ => None,
+        }
+    }
+
+    /// The error the rule failed with, if it did. See [`RuleOutcome::Failed`].
+    pub fn failed(&self) -> Option<&RuleError> {
+        match &self.outcome {
+            RuleOutcome::Failed(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for RuleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)?;
+
+        match (&self.path, self.line) {
+            (Some(path), Some(line)) => write!(f, " ({}:{line})", path.display()),
+            (Some(path), None) => write!(f, " ({})", path.display()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Summary of the last validation run, as recorded by [`VirtLint::validate()`].
+#[derive(Clone, Debug)]
+pub(crate) struct LintRun {
+    pub(crate) input: LintInput,
+    pub(crate) started: SystemTime,
+    pub(crate) duration: Duration,
+}
+
+/// Results of a validation run.
+///
+/// Unlike [`VirtLint::warnings()`] and friends, the report also records the input, the version
+/// of virt-lint (and thus of its linting rules) and timing, and can be serialized (e.g. into
+/// JSON, see [`to_json()`]) to be consumed by other programs.
+///
+/// See [`VirtLint::report()`].
+///
+/// [`to_json()`]: LintReport::to_json
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct LintReport {
+    version: String,
+    input: LintInput,
+    rules: Vec<RuleRun>,
+    warnings: Vec<VirtLintWarning>,
+    suppressed: Vec<SuppressedWarning>,
+    baseline_stale: Vec<BaselineEntry>,
+    started: u64,
+    duration_ms: u64,
+}
+
+impl LintReport {
+    pub(crate) fn new(vl: &VirtLint, run: &LintRun) -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            input: run.input.clone(),
+            rules: vl.rules(),
+            warnings: vl.warnings(),
+            suppressed: vl.suppressed(),
+            baseline_stale: vl.baseline_stale().to_vec(),
+            started: run
+                .started
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            duration_ms: run.duration.as_millis() as u64,
+        }
+    }
+
+    /// Version of virt-lint that produced the report.
+    ///
+    /// Linting rules (both built-in and Lua/Python ones) are shipped with virt-lint, hence this
+    /// identifies them too.
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// The validated input.
+    pub fn input(&self) -> &LintInput {
+        &self.input
+    }
+
+    /// Record where the domain XML came from (e.g. a file name).
+    pub fn source_set(&mut self, source: Option<String>) {
+        self.input.source = source;
+    }
+
+    /// Executed rules, see [`VirtLint::rules()`].
+    pub fn rules(&self) -> &[RuleRun] {
+        &self.rules
+    }
+
+    /// Reported warnings, see [`VirtLint::warnings()`].
+    pub fn warnings(&self) -> &[VirtLintWarning] {
+        &self.warnings
+    }
+
+    /// Warnings waived in domain XML, see [`VirtLint::suppressed()`].
+    pub fn suppressed(&self) -> &[SuppressedWarning] {
+        &self.suppressed
+    }
+
+    /// Baseline entries that no longer occur, see [`VirtLint::baseline_stale()`].
+    pub fn baseline_stale(&self) -> &[BaselineEntry] {
+        &self.baseline_stale
+    }
+
+    /// When the validation started, in seconds since the Unix epoch.
+    pub fn started(&self) -> u64 {
+        self.started
+    }
+
+    /// How long the validation took.
+    pub fn duration(&self) -> Duration {
+        Duration::from_millis(self.duration_ms)
+    }
+
+    /// Format report as (pretty printed) JSON.
+    pub fn to_json(&self) -> VirtLintResult<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Format report as JSON on a single line, e.g. for newline-delimited JSON.
+    pub fn to_json_line(&self) -> VirtLintResult<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+}
@@ -0,0 +1,60 @@
+/* SPDX-License-Identifier: LGPL-3.0-or-later */
+
+use crate::*;
+use libxml::xpath::Context;
+
+/// Namespace of virt-lint elements within domain `<metadata/>`.
+pub const METADATA_NAMESPACE: &str = "https://gitlab.com/MichalPrivoznik/virt-lint/xmlns/1.0";
+
+/// A rule waived in domain XML, e.g.:
+///
+/// ```xml
+/// <metadata>
+///   <vl:lint xmlns:vl="https://gitlab.com/MichalPrivoznik/virt-lint/xmlns/1.0">
+///     <vl:ignore rule="numa.fit" reason="Spans NUMA nodes on purpose"/>
+///   </vl:lint>
+/// </metadata>
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Suppression {
+    /// Either rule ID or tag
+    rule: String,
+    reason: Option<String>,
+}
+
+impl Suppression {
+    pub(crate) fn matches(&self, w: &VirtLintWarning) -> bool {
+        w.id == self.rule || w.tags.contains(&self.rule)
+    }
+
+    pub(crate) fn reason(&self) -> Option<&str> {
+        self.reason.as_deref()
+    }
+}
+
+/// Parse suppressions from domain XML `<metadata/>`.
+pub(crate) fn suppressions_parse(doc: &Document) -> Vec<Suppression> {
+    let ctxt = Context::new(doc).unwrap();
+
+    if ctxt.register_namespace("vl", METADATA_NAMESPACE).is_err() {
+        return Vec::new();
+    }
+
+    // Children of <vl:lint/> don't have to be namespaced themselves.
+    let xpath = "/domain/metadata/vl:lint/*[self::vl:ignore or self::ignore][@rule]";
+
+    let nodes = match ctxt.evaluate(xpath) {
+        Ok(nodes) => nodes.get_nodes_as_vec(),
+        Err(_) => return Vec::new(),
+    };
+
+    nodes
+        .iter()
+        .filter_map(|node| {
+            Some(Suppression {
+                rule: node.get_property("rule")?,
+                reason: node.get_property("reason"),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,183 @@
+/* SPDX-License-Identifier: LGPL-3.0-or-later */
+
+//! Selection of linting rules by tags.
+//!
+//! Each validator tag passed to [`VirtLint::validate()`] is in fact an expression over tags of
+//! a rule, e.g. `common and not common/check_numa_free` or `(TAG_1 or TAG_4) and not TAG_3`.
+//! A plain tag is the simplest expression. Operators are, from the lowest precedence: `or`,
+//! `and`, `not`. Parentheses can be used for grouping.
+//!
+//! [`VirtLint::validate()`]: crate::VirtLint::validate
+
+use crate::errors::*;
+
+const AND: &str = "and";
+const OR: &str = "or";
+const NOT: &str = "not";
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum TagExpr {
+    Tag(String),
+    Not(Box<TagExpr>),
+    And(Box<TagExpr>, Box<TagExpr>),
+    Or(Box<TagExpr>, Box<TagExpr>),
+}
+
+/// Split `expr` into words and parentheses.
+fn tokenize(expr: &str) -> Vec<String> {
+    let mut tokens: Vec<String> = Vec::new();
+    let mut word = String::new();
+
+    for c in expr.chars() {
+        if c.is_whitespace() || c == '(' || c == ')' {
+            if !word.is_empty() {
+                tokens.push(std::mem::take(&mut word));
+            }
+            if !c.is_whitespace() {
+                tokens.push(c.to_string());
+            }
+        } else {
+            word.push(c);
+        }
+    }
+
+    if !word.is_empty() {
+        tokens.push(word);
+    }
+
+    tokens
+}
+
+struct Parser<'a> {
+    expr: &'a str,
+    tokens: Vec<String>,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, msg: &str) -> VirtLintError {
+        VirtLintError::TagExpression(format!("'{}': {msg}", self.expr))
+    }
+
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.pos).map(String::as_str)
+    }
+
+    fn accept(&mut self, token: &str) -> bool {
+        if self.peek() == Some(token) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn or(&mut self) -> VirtLintResult<TagExpr> {
+        let mut ret = self.and()?;
+
+        while self.accept(OR) {
+            ret = TagExpr::Or(Box::new(ret), Box::new(self.and()?));
+        }
+
+        Ok(ret)
+    }
+
+    fn and(&mut self) -> VirtLintResult<TagExpr> {
+        let mut ret = self.not()?;
+
+        while self.accept(AND) {
+            ret = TagExpr::And(Box::new(ret), Box::new(self.not()?));
+        }
+
+        Ok(ret)
+    }
+
+    fn not(&mut self) -> VirtLintResult<TagExpr> {
+        if self.accept(NOT) {
+            return Ok(TagExpr::Not(Box::new(self.not()?)));
+        }
+
+        if self.accept("(") {
+            let ret = self.or()?;
+            if !self.accept(")") {
+                return Err(self.error("missing ')'"));
+            }
+            return Ok(ret);
+        }
+
+        match self.peek() {
+            None => Err(self.error("unexpected end")),
+            Some(t) if [AND, OR, ")"].contains(&t) => Err(self.error(&format!("unexpected '{t}'"))),
+            Some(t) => {
+                let ret = TagExpr::Tag(t.to_string());
+                self.pos += 1;
+                Ok(ret)
+            }
+        }
+    }
+}
+
+impl TagExpr {
+    pub(crate) fn parse(expr: &str) -> VirtLintResult<Self> {
+        let mut parser = Parser {
+            expr,
+            tokens: tokenize(expr),
+            pos: 0,
+        };
+
+        let ret = parser.or()?;
+
+        if let Some(t) = parser.peek() {
+            return Err(parser.error(&format!("unexpected '{t}'")));
+        }
+
+        Ok(ret)
+    }
+
+    /// Tags the expression refers to.
+    pub(crate) fn tags(&self) -> Vec<&str> {
+        match self {
+            Self::Tag(t) => vec![t.as_str()],
+            Self::Not(e) => e.tags(),
+            Self::And(a, b) | Self::Or(a, b) => [a.tags(), b.tags()].concat(),
+        }
+    }
+
+    /// Whether a rule with `tags` matches the expression.
+    pub(crate) fn matches<S: AsRef<str>>(&self, tags: &[S]) -> bool {
+        match self {
+            Self::Tag(t) => tags.iter().any(|x| x.as_ref() == t),
+            Self::Not(e) => !e.matches(tags),
+            Self::And(a, b) => a.matches(tags) && b.matches(tags),
+            Self::Or(a, b) => a.matches(tags) || b.matches(tags),
+        }
+    }
+}
+
+/// Rules selected by a list of tag expressions: a rule is selected if it matches any of them.
+/// An empty list selects all rules.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct TagSelection {
+    exprs: Vec<TagExpr>,
+}
+
+impl TagSelection {
+    pub(crate) fn parse(exprs: &[String]) -> VirtLintResult<Self> {
+        Ok(Self {
+            exprs: exprs
+                .iter()
+                .map(|e| TagExpr::parse(e))
+                .collect::<VirtLintResult<Vec<TagExpr>>>()?,
+        })
+    }
+
+    /// Tags the selection refers to.
+    pub(crate) fn tags(&self) -> Vec<&str> {
+        self.exprs.iter().flat_map(|e| e.tags()).collect()
+    }
+
+    /// Whether a rule with `tags` is selected.
+    pub(crate) fn matches<S: AsRef<str>>(&self, tags: &[S]) -> bool {
+        self.exprs.is_empty() || self.exprs.iter().any(|e| e.matches(tags))
+    }
+}
@@ -11,6 +11,18 @@ use virt::domain::Domain;
 
 static TEST_INIT: Once = Once::new();
 
+/// Domain XML shared by offline tests.
+const Q35_DOMXML: &str = "<domain type='kvm'>
+  <name>q35</name>
+  <memory>1048576</memory>
+  <os>
+    <type arch='x86_64' machine='pc-q35-8.1'>hvm</type>
+  </os>
+  <devices>
+    <controller type='pci' index='0' model='pcie-root'/>
+  </devices>
+</domain>";
+
 fn test_init() {
     TEST_INIT.call_once(|| {
         // Set
@@ -22,6 +34,12 @@ fn test_init() {
             "VIRT_LINT_PYTHON_PATH",
             concat!(env!("CARGO_MANIFEST_DIR"), "/../validators_python"),
         );
+        std::env::set_var(
+            "VIRT_LINT_LOCALE_PATH",
+            concat!(env!("CARGO_MANIFEST_DIR"), "/../po"),
+        );
+        // Expected messages are in English
+        std::env::set_var("LC_ALL", "C");
     });
 }
 
@@ -35,6 +53,51 @@ fn close(mut conn: Connect) {
     assert_eq!(Ok(0), conn.close(), "close(), expected 0")
 }
 
+// All the NUMA related rules point at the <memory/> element of the "test" domain.
+fn memory_location() -> Vec<WarningLocation> {
+    vec![WarningLocation::new(
+        String::from("/domain/memory"),
+        Some(4),
+        Some(3),
+    )]
+}
+
+// The biggest NUMA node of the test driver has 4GiB
+fn numa_fit_fix() -> Option<WarningFix> {
+    Some(WarningFix::new(
+        String::from("Shrink domain memory to fit into the biggest host NUMA node"),
+        vec![XmlEdit::Replace {
+            xpath: String::from("//domain/memory"),
+            xml: String::from("<memory unit='KiB'>4193280</memory>"),
+        }],
+    ))
+}
+
+// Message templates of the NUMA related rules, rendered with their data
+const NUMA_FIT_MSG: &str =
+    "Domain memory ({requested_kib} KiB) would not fit into any host NUMA node";
+const NUMA_FREE_MSG: &str = "Not enough free memory ({requested_kib} KiB) on any NUMA node";
+
+// The "test" domain has 8GiB of memory, while NUMA nodes of the test driver have 2 and 4GiB
+fn numa_fit_data() -> WarningData {
+    WarningData::from([
+        (String::from("requested_kib"), WarningValue::Int(8388608)),
+        (String::from("largest_node_kib"), WarningValue::Int(4194304)),
+    ])
+}
+
+// Free memory of NUMA nodes of the test driver is 2 and 4MiB (reported in bytes)
+fn numa_free_data() -> WarningData {
+    WarningData::from([
+        (String::from("requested_kib"), WarningValue::Int(8388608)),
+        (
+            String::from("free_kib"),
+            WarningValue::List(vec![WarningValue::Int(2048), WarningValue::Int(4096)]),
+        ),
+        (String::from("largest_free_kib"), WarningValue::Int(4096)),
+    ])
+}
+
 #[test]
 fn test_empty() {
     test_init();
@@ -98,46 +161,65 @@ fn test_simple() {
             vec![
                 VirtLintWarning::new(
                     vec![String::from("TAG_1"), String::from("TAG_2")],
+                    String::from("numa.fit"),
                     WarningDomain::Domain,
                     WarningLevel::Error,
-                    String::from("Domain would not fit into any host NUMA node")
-                ),
+                    String::from(NUMA_FIT_MSG),
+                    memory_location()
+                )
+                .with_fix(numa_fit_fix())
+                .with_data(numa_fit_data()),
                 VirtLintWarning::new(
                     vec![String::from("TAG_2")],
+                    String::from("numa.free"),
                     WarningDomain::Domain,
                     WarningLevel::Error,
-                    String::from("Not enough free memory on any NUMA node")
-                ),
+                    String::from(NUMA_FREE_MSG),
+                    memory_location()
+                )
+                .with_data(numa_free_data()),
                 VirtLintWarning::new(
                     vec![String::from("common"), String::from("common/check_numa")],
+                    String::from("common/check_numa"),
                     WarningDomain::Domain,
                     WarningLevel::Error,
-                    String::from("Domain would not fit into any host NUMA node")
-                ),
+                    String::from(NUMA_FIT_MSG),
+                    memory_location()
+                )
+                .with_data(numa_fit_data()),
                 VirtLintWarning::new(
                     vec![
                         String::from("common"),
                         String::from("common/check_numa_free")
                     ],
+                    String::from("common/check_numa_free"),
                     WarningDomain::Domain,
                     WarningLevel::Error,
-                    String::from("Not enough free memory on any NUMA node")
-                ),
+                    String::from(NUMA_FREE_MSG),
+                    memory_location()
+                )
+                .with_data(numa_free_data()),
                 VirtLintWarning::new(
                     vec![String::from("common_p"), String::from("common_p/check_numa")],
+                    String::from("common_p/check_numa"),
                     WarningDomain::Domain,
                     WarningLevel::Error,
-                    String::from("Domain would not fit into any host NUMA node")
-                ),
+                    String::from(NUMA_FIT_MSG),
+                    memory_location()
+                )
+                .with_data(numa_fit_data()),
                 VirtLintWarning::new(
                     vec![
                         String::from("common_p"),
                         String::from("common_p/check_numa_free")
                     ],
+                    String::from("common_p/check_numa_free"),
                     WarningDomain::Domain,
                     WarningLevel::Error,
-                    String::from("Not enough free memory on any NUMA node")
-                ),
+                    String::from(NUMA_FREE_MSG),
+                    memory_location()
+                )
+                .with_data(numa_free_data()),
             ]
         );
     }
@@ -172,7 +254,7 @@ fn test_offline_simple() {
 
     let mut vl = VirtLint::new(None);
 
-    assert!(vl.capabilities_set(Some(capsxml)).is_ok());
+    assert!(vl.capabilities_set(Some(capsxml.clone())).is_ok());
     assert!(vl.domain_capabilities_add(domcapsxml).is_ok());
     assert!(vl.validate(&domxml, &Vec::new(), false).is_ok());
 
@@ -185,24 +267,69 @@ fn test_offline_simple() {
         vec![
             VirtLintWarning::new(
                 vec![String::from("TAG_1"), String::from("TAG_2")],
+                String::from("numa.fit"),
                 WarningDomain::Domain,
                 WarningLevel::Error,
-                String::from("Domain would not fit into any host NUMA node")
-            ),
+                String::from(NUMA_FIT_MSG),
+                memory_location()
+            )
+            .with_fix(numa_fit_fix())
+            .with_data(numa_fit_data()),
             VirtLintWarning::new(
                 vec![String::from("common"), String::from("common/check_numa")],
+                String::from("common/check_numa"),
                 WarningDomain::Domain,
                 WarningLevel::Error,
-                String::from("Domain would not fit into any host NUMA node")
-            ),
+                String::from(NUMA_FIT_MSG),
+                memory_location()
+            )
+            .with_data(numa_fit_data()),
             VirtLintWarning::new(
                 vec![String::from("common_p"), String::from("common_p/check_numa")],
+                String::from("common_p/check_numa"),
                 WarningDomain::Domain,
                 WarningLevel::Error,
-                String::from("Domain would not fit into any host NUMA node")
-            ),
+                String::from(NUMA_FIT_MSG),
+                memory_location()
+            )
+            .with_data(numa_fit_data()),
         ]
     );
+
+    // Memory given in other units is converted to KiB
+    let domxml = domxml.replace(
+        "<memory unit='KiB'>8388608</memory>",
+        "<memory unit='GiB'>8</memory>",
+    );
+
+    assert!(vl.validate(&domxml, &Vec::new(), false).is_ok());
+
+    let mut converted = vl.warnings();
+
+    converted.sort();
+
+    assert_eq!(converted, warnings);
+
+    // And so is memory of NUMA nodes in capabilities
+    let caps_mib = capsxml
+        .replace(
+            "<memory unit='KiB'>2097152</memory>",
+            "<memory unit='MiB'>2048</memory>",
+        )
+        .replace(
+            "<memory unit='KiB'>4194304</memory>",
+            "<memory unit='MiB'>4096</memory>",
+        );
+    assert_ne!(caps_mib, capsxml);
+
+    assert!(vl.capabilities_set(Some(caps_mib)).is_ok());
+    assert!(vl.validate(&domxml, &Vec::new(), false).is_ok());
+
+    let mut converted = vl.warnings();
+
+    converted.sort();
+
+    assert_eq!(converted, warnings);
 }
 
 #[test]
@@ -242,7 +369,7 @@ fn test_offline_with_error() {
     assert!(vl
         .validate(
             &domxml,
-            &vec![
+            &[
                 String::from("TAG_1"),
                 String::from("TAG_3"),
                 String::from("TAG_4"),
@@ -266,22 +393,1031 @@ fn test_offline_with_error() {
         vec![
             VirtLintWarning::new(
                 vec![String::from("TAG_1"), String::from("TAG_2")],
+                String::from("numa.fit"),
                 WarningDomain::Domain,
                 WarningLevel::Error,
-                String::from("Domain would not fit into any host NUMA node")
-            ),
+                String::from(NUMA_FIT_MSG),
+                memory_location()
+            )
+            .with_fix(numa_fit_fix())
+            .with_data(numa_fit_data()),
             VirtLintWarning::new(
                 vec![String::from("common"), String::from("common/check_numa")],
+                String::from("common/check_numa"),
                 WarningDomain::Domain,
                 WarningLevel::Error,
-                String::from("Domain would not fit into any host NUMA node")
-            ),
+                String::from(NUMA_FIT_MSG),
+                memory_location()
+            )
+            .with_data(numa_fit_data()),
             VirtLintWarning::new(
                 vec![String::from("common_p"), String::from("common_p/check_numa")],
+                String::from("common_p/check_numa"),
                 WarningDomain::Domain,
                 WarningLevel::Error,
-                String::from("Domain would not fit into any host NUMA node")
-            ),
+                String::from(NUMA_FIT_MSG),
+                memory_location()
+            )
+            .with_data(numa_fit_data()),
         ]
     );
 }
+
+#[test]
+fn test_locations() {
+    test_init();
+
+    let domxml = Q35_DOMXML;
+
+    let mut vl = VirtLint::new(None);
+
+    assert!(vl.validate(domxml, &[String::from("TAG_4")], false).is_ok());
+
+    let warnings = vl.warnings();
+
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].id(), "pcie.root-ports.free");
+    assert_eq!(
+        warnings[0].locations(),
+        [WarningLocation::new(
+            String::from("/domain/devices"),
+            Some(7),
+            Some(3)
+        )]
+    );
+    assert_eq!(
+        warnings[0].locations()[0].to_string(),
+        "/domain/devices:7:3"
+    );
+    assert_eq!(
+        warnings[0].data(),
+        &WarningData::from([(String::from("occupied_chassis"), WarningValue::List(vec![]))])
+    );
+}
+
+#[test]
+fn test_fixes() {
+    test_init();
+
+    let domxml = Q35_DOMXML;
+
+    let mut vl = VirtLint::new(None);
+
+    assert!(vl.validate(domxml, &[String::from("TAG_4")], false).is_ok());
+
+    let warnings = vl.warnings();
+    let fixes: Vec<&WarningFix> = warnings.iter().filter_map(|w| w.fix()).collect();
+
+    assert_eq!(fixes.len(), 1);
+    assert_eq!(
+        VirtLint::apply_fixes(domxml, &fixes).unwrap(),
+        "<domain type=\"kvm\">
+  <name>q35</name>
+  <memory>1048576</memory>
+  <os>
+    <type arch=\"x86_64\" machine=\"pc-q35-8.1\">hvm</type>
+  </os>
+  <devices>
+    <controller type=\"pci\" index=\"0\" model=\"pcie-root\"/>
+    <controller type=\"pci\" model=\"pcie-root-port\"/>
+  </devices>
+</domain>
+"
+    );
+
+    let fix = WarningFix::new(
+        String::from("Test fix"),
+        vec![
+            XmlEdit::Replace {
+                xpath: String::from("//domain/memory"),
+                xml: String::from("<memory unit='KiB'>524288</memory>"),
+            },
+            XmlEdit::Remove {
+                xpath: String::from("//domain/os/type/@machine"),
+            },
+            XmlEdit::Remove {
+                xpath: String::from("//domain/devices/controller"),
+            },
+        ],
+    );
+
+    assert_eq!(
+        VirtLint::apply_fixes(domxml, &[&fix]).unwrap(),
+        "<domain type=\"kvm\">
+  <name>q35</name>
+  <memory unit=\"KiB\">524288</memory>
+  <os>
+    <type arch=\"x86_64\">hvm</type>
+  </os>
+  <devices>
+  </devices>
+</domain>
+"
+    );
+
+    // Memory is shrunk to whole MiB smaller than the biggest NUMA node, current memory too
+    let capsxml = "<capabilities>
+  <host>
+    <topology>
+      <cells num='1'>
+        <cell id='0'>
+          <memory unit='KiB'>1536</memory>
+        </cell>
+      </cells>
+    </topology>
+  </host>
+</capabilities>";
+
+    let small = domxml.replace(
+        "<memory>1048576</memory>",
+        "<memory>1048576</memory>\n  <currentMemory>1048576</currentMemory>",
+    );
+
+    assert!(vl.capabilities_set(Some(capsxml.to_string())).is_ok());
+    assert!(vl.validate(&small, &[String::from("TAG_1")], false).is_ok());
+
+    let warnings = vl.warnings();
+    let fix = warnings
+        .iter()
+        .find(|w| w.id() == "numa.fit")
+        .and_then(|w| w.fix())
+        .unwrap();
+
+    assert_eq!(
+        fix.edits(),
+        [
+            XmlEdit::Replace {
+                xpath: String::from("//domain/memory"),
+                xml: String::from("<memory unit='KiB'>1024</memory>"),
+            },
+            XmlEdit::Replace {
+                xpath: String::from("//domain/currentMemory"),
+                xml: String::from("<currentMemory unit='KiB'>1024</currentMemory>"),
+            },
+        ]
+    );
+
+    // No fix if the node is smaller than 1MiB
+    let capsxml = capsxml.replace(">1536<", ">1024<");
+
+    assert!(vl.capabilities_set(Some(capsxml)).is_ok());
+    assert!(vl.validate(&small, &[String::from("TAG_1")], false).is_ok());
+
+    let warnings = vl.warnings();
+    let warning = warnings.iter().find(|w| w.id() == "numa.fit").unwrap();
+    assert!(warning.fix().is_none());
+
+    // XPath matching nothing is an error
+    assert!(VirtLint::apply_fixes(
+        domxml,
+        &[&WarningFix::new(
+            String::from("Test fix"),
+            vec![XmlEdit::Remove {
+                xpath: String::from("//domain/vcpu"),
+            }],
+        )]
+    )
+    .is_err());
+}
+
+#[test]
+fn test_config() {
+    test_init();
+
+    let domxml = Q35_DOMXML;
+
+    let tags = [String::from("TAG_4")];
+    let mut vl = VirtLint::new(None);
+
+    // Level override by rule ID
+    let mut config = VirtLintConfig::new();
+    config.rule_set(
+        "pcie.root-ports.free",
+        RuleSetting::Level(WarningLevel::Error),
+    );
+    vl.config_set(config.clone());
+
+    assert!(vl.validate(domxml, &tags, false).is_ok());
+
+    let warnings = vl.warnings();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].get().2, &WarningLevel::Error);
+
+    // Rule ID wins over tag
+    config.rule_set("TAG_4", RuleSetting::Disabled);
+    vl.config_set(config.clone());
+
+    assert!(vl.validate(domxml, &tags, false).is_ok());
+    assert_eq!(vl.warnings().len(), 1);
+
+    // Disable by tag, disabled rules are not run at all
+    config.rule_unset("pcie.root-ports.free");
+    vl.config_set(config);
+
+    assert!(vl.validate(domxml, &tags, false).is_ok());
+    assert!(vl.warnings().is_empty());
+    assert!(vl.rules().is_empty());
+
+    // Not even those that would fail for missing connection
+    let mut config = VirtLintConfig::new();
+    config.rule_set("TAG_2", RuleSetting::Disabled);
+    config.rule_set("common", RuleSetting::Disabled);
+    vl.config_set(config);
+
+    let tags_online = [String::from("TAG_2"), String::from("common")];
+    assert!(vl.validate(domxml, &tags_online, true).is_ok());
+    assert!(vl.rules().is_empty());
+
+    // Minimal level, dropped warnings are not counted
+    let mut config = VirtLintConfig::new();
+    config.min_level_set(Some(WarningLevel::Warning));
+    vl.config_set(config);
+
+    assert!(vl.validate(domxml, &tags, false).is_ok());
+    assert!(vl.warnings().is_empty());
+
+    let rules = vl.rules();
+    assert_eq!(rules[0].id(), "pcie.root-ports.free");
+    assert_eq!(rules[0].outcome(), &RuleOutcome::Ran);
+    assert_eq!(rules[0].warning_count(), 0);
+}
+
+#[test]
+fn test_suppressions() {
+    test_init();
+
+    let domxml = Q35_DOMXML.replace(
+        "<memory>",
+        "<metadata>
+    <vl:lint xmlns:vl='https://gitlab.com/MichalPrivoznik/virt-lint/xmlns/1.0'>
+      <vl:ignore rule='pcie.root-ports.free' reason='No hotplug needed'/>
+    </vl:lint>
+  </metadata>
+  <memory>",
+    );
+
+    let tags = [String::from("TAG_4")];
+    let mut vl = VirtLint::new(None);
+
+    assert!(vl.validate(&domxml, &tags, false).is_ok());
+
+    assert!(vl.warnings().is_empty());
+
+    let suppressed = vl.suppressed();
+    assert_eq!(suppressed.len(), 1);
+    assert_eq!(suppressed[0].warning().id(), "pcie.root-ports.free");
+    assert_eq!(suppressed[0].reason(), Some("No hotplug needed"));
+
+    // Unqualified <ignore/> within <vl:lint/> works too
+    let unqualified = domxml.replace("<vl:ignore", "<ignore");
+
+    assert!(vl.validate(&unqualified, &tags, false).is_ok());
+
+    assert!(vl.warnings().is_empty());
+    assert_eq!(vl.suppressed().len(), 1);
+
+    // Elements from other namespaces are ignored
+    let domxml = domxml.replace("xmlns/1.0", "xmlns/0.0");
+
+    assert!(vl.validate(&domxml, &tags, false).is_ok());
+
+    assert_eq!(vl.warnings().len(), 1);
+    assert!(vl.suppressed().is_empty());
+}
+
+#[test]
+fn test_baseline() {
+    test_init();
+
+    let domxml = Q35_DOMXML.replace(
+        "<memory>",
+        "<uuid>C7A5FDBD-EDAF-9455-926A-D65C16DB1809</uuid>
+  <memory>",
+    );
+
+    let tags = [String::from("TAG_4")];
+    let mut vl = VirtLint::new(None);
+
+    assert!(vl.validate(&domxml, &tags, false).is_ok());
+    assert_eq!(vl.warnings().len(), 1);
+
+    let mut baseline = Baseline::new();
+    assert!(baseline.add(&domxml, &vl.warnings()).is_ok());
+
+    let entries = baseline.entries();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].rule(), "pcie.root-ports.free");
+    assert_eq!(entries[0].domain_name(), Some("q35"));
+    assert_eq!(
+        entries[0].domain_uuid(),
+        Some("c7a5fdbd-edaf-9455-926a-d65c16db1809")
+    );
+    assert_eq!(entries[0].locations(), ["/domain/devices"]);
+
+    let json = baseline.to_json().unwrap();
+    assert_eq!(Baseline::from_json(&json).unwrap(), baseline);
+
+    // Known warnings are not reported, nor counted
+    vl.baseline_set(Some(baseline));
+
+    assert!(vl.validate(&domxml, &tags, false).is_ok());
+    assert!(vl.warnings().is_empty());
+    assert!(vl.baseline_stale().is_empty());
+    assert_eq!(vl.rules()[0].warning_count(), 0);
+
+    // Baseline entries that no longer occur are reported
+    let fixed = domxml.replace(
+        "</devices>",
+        "  <controller type='pci' index='1' model='pcie-root-port'/>\n  </devices>",
+    );
+
+    assert!(vl.validate(&fixed, &tags, false).is_ok());
+    assert!(vl.warnings().is_empty());
+    assert_eq!(vl.baseline_stale().len(), 1);
+
+    // Baseline is per domain
+    let other = domxml
+        .replace("<name>q35</name>", "<name>other</name>")
+        .replace("C7A5FDBD", "D7A5FDBD");
+
+    assert!(vl.validate(&other, &tags, false).is_ok());
+    assert_eq!(vl.warnings().len(), 1);
+    assert!(vl.baseline_stale().is_empty());
+
+    // Entries of rules that did not run are not stale, e.g. a baseline recorded online
+    // contains numa.free which is skipped offline
+    let baseline = Baseline::from_json(
+        r#"{
+  "version": 1,
+  "entries": [
+    { "rule": "node.kvm", "domain_name": "q35" },
+    { "rule": "numa.free", "domain_name": "q35", "locations": ["/domain/memory"] },
+    { "rule": "pcie.root-ports.free", "domain_name": "q35", "locations": ["/domain/devices"] }
+  ]
+}"#,
+    )
+    .unwrap();
+    vl.baseline_set(Some(baseline));
+
+    let tags = [String::from("TAG_2"), String::from("TAG_4")];
+
+    assert!(vl.validate(&fixed, &tags, false).is_ok());
+    let rules = vl.rules();
+    let numa_free = rules.iter().find(|r| r.id() == "numa.free").unwrap();
+    assert!(numa_free.skipped().is_some());
+
+    let stale = vl.baseline_stale();
+    assert_eq!(stale.len(), 1);
+    assert_eq!(stale[0].rule(), "pcie.root-ports.free");
+}
+
+#[test]
+fn test_catalog() {
+    test_init();
+
+    let domxml = Q35_DOMXML;
+
+    let tags = [String::from("TAG_4")];
+    let template =
+        "No free PCIe root ports found (occupied: {occupied_chassis}), hotplug might be not possible";
+    let mut vl = VirtLint::new(None);
+
+    // Shipped catalog
+    assert!(vl.locale_set(Some("cs_CZ.UTF-8")).is_ok());
+    assert!(vl.validate(domxml, &tags, false).is_ok());
+
+    let warnings = vl.warnings();
+    assert_eq!(
+        warnings[0].get().3,
+        "Nebyly nalezeny žádné volné PCIe root porty (obsazené: []), hotplug nemusí být možný"
+    );
+    assert_eq!(warnings[0].template(), template);
+
+    // Translations are shared by rules emitting the same message
+    let script = [String::from("common/check_pcie_root_ports")];
+    assert!(vl.validate(domxml, &script, false).is_ok());
+    assert_eq!(vl.warnings()[0].get().3, warnings[0].get().3);
+
+    // No translation
+    assert!(vl.locale_set(Some("C")).is_ok());
+    assert!(vl.validate(domxml, &tags, false).is_ok());
+    assert_eq!(
+        vl.warnings()[0].get().3,
+        "No free PCIe root ports found (occupied: []), hotplug might be not possible"
+    );
+
+    // Placeholders, fuzzy entries. Only placeholders with a value are replaced.
+    let catalog = Catalog::from_po(
+        r#"
+msgctxt "pcie.root-ports.free"
+msgid "No free PCIe root ports found (occupied: {occupied_chassis}), hotplug might be not possible"
+msgstr "Occupied: {occupied_chassis}, "
+"unknown: {unknown}, braces: {{}}"
+
+#, fuzzy
+msgctxt "numa.fit"
+msgid "Domain memory ({requested_kib} KiB) would not fit into any host NUMA node"
+msgstr "Fuzzy"
+"#,
+    )
+    .unwrap();
+
+    vl.catalog_set(Some(catalog));
+    assert!(vl.validate(domxml, &tags, false).is_ok());
+    assert_eq!(
+        vl.warnings()[0].get().3,
+        "Occupied: [], unknown: {unknown}, braces: {{}}"
+    );
+
+    assert!(Catalog::from_po("msgid \"unterminated").is_err());
+}
+
+#[test]
+fn test_report() {
+    test_init();
+
+    let domxml = Q35_DOMXML;
+
+    let tags = [String::from("TAG_4")];
+    let mut vl = VirtLint::new(None);
+
+    assert!(vl.report().is_none());
+    assert!(vl.validate(domxml, &tags, false).is_ok());
+
+    let mut report = vl.report().unwrap();
+    report.source_set(Some(String::from("q35.xml")));
+
+    assert_eq!(report.version(), env!("CARGO_PKG_VERSION"));
+    assert_eq!(report.input().source(), Some("q35.xml"));
+    assert_eq!(report.input().domain_name(), Some("q35"));
+    assert_eq!(report.input().domain_uuid(), None);
+    assert_eq!(report.input().validator_tags(), tags);
+    assert_eq!(report.warnings(), vl.warnings());
+    assert!(report.suppressed().is_empty());
+    assert!(report.baseline_stale().is_empty());
+
+    let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+    let w = &json["warnings"][0];
+    assert_eq!(json["input"]["domain_name"], "q35");
+    assert_eq!(w["id"], "pcie.root-ports.free");
+    assert_eq!(w["level"], "Notice");
+    assert_eq!(w["locations"][0]["xpath"], "/domain/devices");
+    assert_eq!(w["fix"]["edits"][0]["op"], "insert");
+
+    // Newline-delimited JSON
+    let line = report.to_json_line().unwrap();
+    assert!(!line.contains('\n'));
+    let parsed: serde_json::Value = serde_json::from_str(&line).unwrap();
+    assert_eq!(parsed, json);
+}
+
+#[test]
+fn test_rules() {
+    test_init();
+
+    let domxml = Q35_DOMXML;
+
+    let tags = [String::from("TAG_1"), String::from("TAG_4")];
+    let mut vl = VirtLint::new(None);
+
+    assert!(vl.validate(domxml, &tags, false).is_ok());
+
+    let rules = vl.rules();
+    let ids: Vec<&str> = rules.iter().map(|r| r.id()).collect();
+    assert_eq!(ids, ["numa.fit", "node.kvm", "pcie.root-ports.free"]);
+    assert_eq!(rules[0].tags(), ["TAG_1", "TAG_2"]);
+
+    // Rules that need host information are skipped in the offline mode
+    assert_eq!(
+        rules[0].skipped(),
+        Some("no connection and no capabilities")
+    );
+    assert_eq!(
+        rules[1].outcome(),
+        &RuleOutcome::Skipped(String::from(
+            "no connection and no domain capabilities for arch x86_64, machine pc-q35-8.1, type kvm"
+        ))
+    );
+    assert_eq!(rules[2].outcome(), &RuleOutcome::Ran);
+    assert_eq!(rules[2].skipped(), None);
+
+    // Each warning is accounted to the rule that emitted it
+    assert!(rules.iter().all(|r| r.engine() == ValidatorEngine::Builtin));
+    let count: usize = rules.iter().map(|r| r.warning_count()).sum();
+    assert_eq!(count, vl.warnings().len());
+
+    assert_eq!(vl.report().unwrap().rules(), rules);
+
+    // Rules that check what they can (node.kvm falls back to capabilities) are not skipped
+    let capsxml = "<capabilities>
+  <host>
+    <topology>
+      <cells num='1'>
+        <cell id='0'>
+          <memory unit='KiB'>4194304</memory>
+        </cell>
+      </cells>
+    </topology>
+  </host>
+</capabilities>";
+
+    assert!(vl.capabilities_set(Some(capsxml.to_string())).is_ok());
+    assert!(vl.validate(domxml, &tags, false).is_ok());
+
+    assert!(vl.rules().iter().all(|r| r.outcome() == &RuleOutcome::Ran));
+
+    assert!(vl.capabilities_set(None).is_ok());
+
+    // Or fail, if connection is required
+    assert!(vl.validate(domxml, &tags, true).is_err());
+
+    let rules = vl.rules();
+    assert_eq!(rules.len(), 1);
+    assert_eq!(rules[0].id(), "numa.fit");
+    let error = rules[0].failed().unwrap();
+    assert!(error.message().contains("no connection"));
+    assert_eq!(error.path(), None);
+
+    // Rules are not skipped once there is a connection
+    let c = conn();
+    {
+        let mut vl = VirtLint::new(Some(&c));
+
+        assert!(vl.validate(domxml, &tags, false).is_ok());
+        assert!(vl.rules().iter().all(|r| r.skipped().is_none()));
+
+        let count: usize = vl.rules().iter().map(|r| r.warning_count()).sum();
+        assert_eq!(count, vl.warnings().len());
+    }
+
+    close(c);
+}
+
+#[test]
+fn test_rules_no_caps() {
+    test_init();
+
+    let mut vl = VirtLint::new(None);
+
+    // Scripts which need information about the host are skipped offline too
+    assert!(vl.validate(Q35_DOMXML, &[], false).is_ok());
+
+    let rules = vl.rules();
+    let scripts: Vec<&RuleRun> = rules
+        .iter()
+        .filter(|r| r.engine() != ValidatorEngine::Builtin)
+        .collect();
+
+    assert_eq!(scripts.len(), 8);
+    for r in scripts {
+        if r.id().ends_with("/check_pcie_root_ports") {
+            assert_eq!(r.outcome(), &RuleOutcome::Ran, "{}", r.id());
+        } else {
+            assert!(r.skipped().is_some(), "{}: {:?}", r.id(), r.outcome());
+        }
+    }
+}
+
+#[test]
+fn test_fork() {
+    test_init();
+
+    let domxml = Q35_DOMXML;
+
+    let tags = [String::from("TAG_4")];
+    let c = conn();
+    let mut config = VirtLintConfig::new();
+    let level = RuleSetting::Level(WarningLevel::Warning);
+    config.rule_set("pcie.root-ports.free", level);
+
+    {
+        let mut vl = VirtLint::new(Some(&c));
+        vl.config_set(config);
+
+        // Forks can be used from other threads and have their own results
+        let handles: Vec<_> = (0..2)
+            .map(|i| {
+                let mut fork = vl.fork();
+                let domxml = domxml.replace("<name>q35", &format!("<name>q35-{i}"));
+                let tags = tags.clone();
+
+                std::thread::spawn(move || {
+                    assert!(fork.validate(&domxml, &tags, false).is_ok());
+                    fork.report().unwrap()
+                })
+            })
+            .collect();
+
+        for (i, h) in handles.into_iter().enumerate() {
+            let report = h.join().unwrap();
+            let name = format!("q35-{i}");
+
+            assert_eq!(report.input().domain_name(), Some(name.as_str()));
+            assert_eq!(report.warnings().len(), 1);
+            assert_eq!(report.warnings()[0].get().2, &WarningLevel::Warning);
+        }
+
+        assert!(vl.warnings().is_empty());
+        assert!(vl.report().is_none());
+    }
+
+    close(c);
+}
+
+#[test]
+fn test_validate_domain() {
+    test_init();
+
+    let c = conn();
+    {
+        let dom = Domain::lookup_by_name(&c, "test").unwrap();
+        let domxml = dom.get_xml_desc(0).unwrap();
+
+        let mut vl = VirtLint::new(Some(&c));
+
+        assert!(vl.validate(&domxml, &[], false).is_ok());
+        let expected = vl.warnings();
+        assert!(!expected.is_empty());
+
+        assert!(vl.validate_domain(&dom, 0, &[], false).is_ok());
+        assert_eq!(vl.warnings(), expected);
+        assert_eq!(vl.report().unwrap().input().domain_name(), Some("test"));
+
+        let flags = virt::sys::VIR_DOMAIN_XML_INACTIVE;
+        assert!(vl.validate_domain(&dom, flags, &[], false).is_ok());
+        assert_eq!(vl.warnings(), expected);
+    }
+
+    close(c);
+}
+
+#[test]
+fn test_rule_docs() {
+    test_init();
+
+    let config = VirtLintConfig::new();
+    let docs = VirtLint::rule_docs(&config).unwrap();
+    let ids: Vec<&str> = docs.iter().map(|d| d.id()).collect();
+    assert_eq!(
+        ids,
+        [
+            "common/check_node_kvm",
+            "common/check_numa",
+            "common/check_numa_free",
+            "common/check_pcie_root_ports",
+            "common_p/check_node_kvm",
+            "common_p/check_numa",
+            "common_p/check_numa_free",
+            "common_p/check_pcie_root_ports",
+            "node.kvm",
+            "numa.fit",
+            "numa.free",
+            "pcie.root-ports.free",
+        ]
+    );
+    assert!(docs.iter().all(|d| !d.summary().is_empty()));
+
+    let rule_doc = |id: &str| VirtLint::rule_doc(&config, id).unwrap();
+
+    let doc = rule_doc("numa.fit").unwrap();
+    assert_eq!(doc.tags(), ["TAG_1", "TAG_2"]);
+    assert_eq!(doc.summary(), "Domain fits into a host NUMA node");
+    assert!(doc.rationale().is_some());
+    assert_eq!(
+        doc.references(),
+        ["https://libvirt.org/formatdomain.html#numa-node-tuning"]
+    );
+
+    // Lua header comment and Python docstring
+    let lua = rule_doc("common/check_numa").unwrap();
+    let python = rule_doc("common_p/check_numa").unwrap();
+    assert_eq!(lua.summary(), doc.summary());
+    assert_eq!(python.summary(), doc.summary());
+    assert_eq!(lua.references(), python.references());
+    assert_eq!(lua.level(), Some(WarningLevel::Error));
+    assert_eq!(python.needs(), Some(&[String::from("capabilities")][..]));
+
+    assert_eq!(rule_doc("nonexistent"), None);
+
+    let doc = RuleDoc::parse(
+        "id",
+        vec![],
+        &crate::docs::header_comment("-- Summary\n--\n-- Some\n-- text.\nlocal x = 1\n", "--"),
+    );
+    assert_eq!(doc.summary(), "Summary");
+    assert_eq!(doc.description(), "Some text.");
+    assert_eq!(doc.rationale(), None);
+    assert_eq!(doc.level(), None);
+    assert_eq!(doc.needs(), None);
+
+    let doc = RuleDoc::parse("id", vec![], "Summary\n\nLevel: notice\nNeeds: none");
+    assert_eq!(doc.description(), "");
+    assert_eq!(doc.level(), Some(WarningLevel::Notice));
+    assert_eq!(doc.needs(), Some(&[][..]));
+}
+
+#[test]
+fn test_config_file() {
+    test_init();
+
+    let system = VirtLintConfig::from_toml(
+        r#"
+uri = "qemu:///system"
+format = "json"
+
+[validators]
+enabled = ["TAG_1"]
+lua_path = ["/srv/validators_lua"]
+
+[rules]
+"numa.fit" = "warning"
+"TAG_4" = "disabled"
+
+[params."common/check_numa"]
+margin_kib = 1024
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(system.uri(), Some("qemu:///system"));
+    assert_eq!(system.format(), Some("json"));
+    assert_eq!(system.validator_tags(), ["TAG_1"]);
+    assert_eq!(
+        system.lua_paths(),
+        [std::path::PathBuf::from("/srv/validators_lua")]
+    );
+    assert!(system.python_paths().is_empty());
+    assert_eq!(
+        system.rule_get("numa.fit"),
+        Some(RuleSetting::Level(WarningLevel::Warning))
+    );
+    assert_eq!(system.rule_get("TAG_4"), Some(RuleSetting::Disabled));
+    assert_eq!(
+        system.rule_param("common/check_numa", "margin_kib"),
+        Some(&WarningValue::Int(1024))
+    );
+
+    let project = VirtLintConfig::from_toml(
+        r#"
+min_level = "warning"
+keep_going = true
+
+[validators]
+disabled = ["TAG_3"]
+
+[rules]
+"numa.fit" = "notice"
+
+[params."common/check_numa"]
+strict = true
+"#,
+    )
+    .unwrap();
+
+    // Settings of the project config take precedence, others are kept
+    let mut config = system;
+    config.merge(project);
+
+    assert_eq!(config.uri(), Some("qemu:///system"));
+    assert_eq!(config.min_level(), Some(WarningLevel::Warning));
+    assert!(config.keep_going());
+    assert_eq!(config.validator_tags(), ["TAG_1"]);
+    assert_eq!(
+        config.rule_get("numa.fit"),
+        Some(RuleSetting::Level(WarningLevel::Notice))
+    );
+    assert_eq!(config.rule_get("TAG_3"), Some(RuleSetting::Disabled));
+    assert_eq!(config.rule_params("common/check_numa").unwrap().len(), 2);
+
+    assert!(VirtLintConfig::from_toml("unknown = 1").is_err());
+    assert!(VirtLintConfig::from_toml("[rules]\n\"numa.fit\" = \"fatal\"").is_err());
+}
+
+#[test]
+fn test_list_validators() {
+    test_init();
+
+    let config = VirtLintConfig::new();
+    let validators = VirtLint::list_validators(&config).unwrap();
+    let ids: Vec<&str> = validators.iter().map(|v| v.id()).collect();
+    assert_eq!(
+        ids,
+        [
+            "common/check_node_kvm",
+            "common/check_numa",
+            "common/check_numa_free",
+            "common/check_pcie_root_ports",
+            "common_p/check_node_kvm",
+            "common_p/check_numa",
+            "common_p/check_numa_free",
+            "common_p/check_pcie_root_ports",
+            "node.kvm",
+            "numa.fit",
+            "numa.free",
+            "pcie.root-ports.free",
+        ]
+    );
+    assert!(validators.iter().all(|v| !v.description().is_empty()));
+
+    let validator = |id: &str| validators.iter().find(|v| v.id() == id).unwrap();
+
+    let v = validator("numa.free");
+    assert_eq!(v.engine(), ValidatorEngine::Builtin);
+    assert_eq!(v.path(), None);
+    assert_eq!(v.tags(), ["TAG_2"]);
+    assert_eq!(v.level(), Some(WarningLevel::Error));
+    assert_eq!(v.needs_connection(), Some(true));
+    assert_eq!(v.needs_capabilities(), Some(true));
+    assert_eq!(v.needs_domain_capabilities(), Some(false));
+
+    let v = validator("common/check_pcie_root_ports");
+    assert_eq!(v.engine(), ValidatorEngine::Lua);
+    let path = v.path().unwrap();
+    assert!(path.ends_with("common/check_pcie_root_ports.lua"));
+    assert_eq!(v.level(), Some(WarningLevel::Notice));
+    assert_eq!(v.needs_connection(), Some(false));
+    assert_eq!(v.needs_capabilities(), Some(false));
+    assert_eq!(v.needs_domain_capabilities(), Some(false));
+
+    let v = validator("common_p/check_numa_free");
+    assert_eq!(v.engine(), ValidatorEngine::Python);
+    let path = v.path().unwrap();
+    assert!(path.ends_with("common_p/check_numa_free.py"));
+    assert_eq!(v.tags(), ["common_p/check_numa_free", "common_p"]);
+    assert_eq!(v.level(), Some(WarningLevel::Error));
+    assert_eq!(v.needs_connection(), Some(true));
+    assert_eq!(v.needs_capabilities(), Some(true));
+
+    let v = validator("common/check_node_kvm");
+    assert_eq!(v.needs_connection(), Some(false));
+    assert_eq!(v.needs_domain_capabilities(), Some(true));
+}
+
+#[test]
+fn test_tag_expressions() {
+    test_init();
+
+    let c = conn();
+    {
+        let dom = Domain::lookup_by_name(&c, "test").unwrap();
+        let domxml = dom.get_xml_desc(0).unwrap_or_default();
+        let mut vl = VirtLint::new(Some(&c));
+
+        let mut run = |tags: &[&str]| -> Vec<String> {
+            let tags: Vec<String> = tags.iter().map(|t| t.to_string()).collect();
+            vl.validate(&domxml, &tags, false).unwrap();
+            vl.rules().iter().map(|r| r.id().to_string()).collect()
+        };
+
+        assert_eq!(
+            run(&["common and not common/check_numa_free"]),
+            [
+                "common/check_node_kvm",
+                "common/check_numa",
+                "common/check_pcie_root_ports"
+            ]
+        );
+        assert_eq!(
+            run(&["(TAG_1 or TAG_4) and not TAG_3"]),
+            ["numa.fit", "pcie.root-ports.free"]
+        );
+
+        // A rule is run if it matches any of the expressions
+        assert_eq!(
+            run(&["TAG_3", "not TAG_2 and not common and not common_p"]),
+            ["node.kvm", "pcie.root-ports.free"]
+        );
+
+        assert!(matches!(
+            vl.validate(&domxml, &[String::from("TAG_1 and")], false),
+            Err(VirtLintError::TagExpression(_))
+        ));
+        assert!(matches!(
+            vl.validate(&domxml, &[String::from("not TAG_5")], false),
+            Err(VirtLintError::UnknownValidatorTag(_))
+        ));
+    }
+
+    close(c);
+}
+
+#[test]
+fn test_keep_going() {
+    test_init();
+
+    let domxml = Q35_DOMXML;
+
+    let tags = [String::from("TAG_1"), String::from("TAG_4")];
+    let mut config = VirtLintConfig::new();
+    config.keep_going_set(true);
+
+    let mut vl = VirtLint::new(None);
+    vl.config_set(config);
+
+    // Broken rules fail, but the others are still run
+    let base = std::env::temp_dir().join(format!("virt-lint-keep-going-{}", std::process::id()));
+    std::fs::create_dir_all(base.join("broken")).unwrap();
+    std::fs::write(base.join("broken/check_a.lua"), "local x = \n").unwrap();
+    std::fs::write(base.join("broken/check_b.py"), [0xff, 0xfe]).unwrap();
+    std::fs::write(base.join("broken/check_c.py"), "x = 1\n").unwrap();
+
+    let mut validators = crate::validators::Validators::new_paths(vec![base.clone()]);
+    assert!(validators
+        .validate(&[String::from("broken")], &mut vl, domxml)
+        .is_ok());
+    std::fs::remove_dir_all(&base).unwrap();
+
+    let rules = vl.rules();
+    let ids: Vec<&str> = rules.iter().map(|r| r.id()).collect();
+    assert_eq!(ids, ["broken/check_a", "broken/check_b", "broken/check_c"]);
+    assert!(rules[0].failed().is_some());
+    assert!(rules[1].failed().is_some());
+    assert_eq!(rules[2].outcome(), &RuleOutcome::Ran);
+
+    // Missing connection fails validation even so
+    assert!(vl.validate(domxml, &tags, true).is_err());
+
+    let rules = vl.rules();
+    assert_eq!(rules[0].id(), "numa.fit");
+    assert!(rules[0].failed().is_some());
+
+    // Errors of scripts carry location and traceback
+    let path = std::path::Path::new("/srv/validators_lua/check_broken.lua");
+    let err = mlua::Lua::new()
+        .load("local x = 1\nerror('broken')")
+        .set_name(format!("@{}", path.display()))
+        .exec()
+        .unwrap_err();
+
+    let error = RuleError::new(&err.into(), Some(path));
+    assert!(error.message().contains("broken"));
+    assert_eq!(error.path(), Some(path));
+    assert_eq!(error.line(), Some(2));
+    assert!(error.traceback().unwrap().starts_with("stack traceback:"));
+
+    let path = std::path::Path::new("/srv/validators_python/check_broken.py");
+    pyo3::prepare_freethreaded_python();
+    let err = pyo3::Python::with_gil(|py| {
+        pyo3::types::PyModule::from_code_bound(
+            py,
+            "x = 1\nraise ValueError('broken')",
+            "/srv/validators_python/check_broken.py",
+            "check_broken",
+        )
+        .unwrap_err()
+    });
+
+    let error = RuleError::new(&err.into(), Some(path));
+    assert_eq!(error.message(), "Python error: ValueError: broken");
+    assert_eq!(error.line(), Some(2));
+    assert!(error.traceback().unwrap().ends_with("ValueError: broken\n"));
+
+    // So do panics
+    assert!(matches!(
+        crate::utils::catch_panic(|| panic!("broken")),
+        Err(VirtLintError::Panic(msg)) if msg == "broken"
+    ));
+}
+
+#[test]
+fn test_validator_override() {
+    let base = std::env::temp_dir().join(format!("virt-lint-override-{}", std::process::id()));
+    let system = base.join("system");
+    let local = base.join("local");
+
+    for dir in [&system, &local] {
+        std::fs::create_dir_all(dir.join("common")).unwrap();
+        std::fs::write(
+            dir.join("common/check_dup.lua"),
+            format!("-- Duplicate validator in {}\n", dir.display()),
+        )
+        .unwrap();
+    }
+    std::fs::write(system.join("common/check_only.lua"), "-- Only validator\n").unwrap();
+
+    let validators = crate::validators::Validators::new_paths(vec![system.clone(), local.clone()]);
+    let list = validators.list().unwrap();
+    let lua: Vec<&ValidatorInfo> = list
+        .iter()
+        .filter(|v| v.engine() == ValidatorEngine::Lua)
+        .collect();
+
+    let ids: Vec<&str> = lua.iter().map(|v| v.id()).collect();
+    assert_eq!(ids, ["common/check_dup", "common/check_only"]);
+    assert!(lua[0].path().unwrap().starts_with(&local));
+    assert!(lua[1].path().unwrap().starts_with(&system));
+
+    // Level and needs are unknown unless declared
+    assert_eq!(lua[1].level(), None);
+    assert_eq!(lua[1].needs_connection(), None);
+
+    // Rule IDs are unique across engines
+    std::fs::write(system.join("common/check_only.py"), "# Only validator\n").unwrap();
+    let validators = crate::validators::Validators::new_paths(vec![system.clone(), local.clone()]);
+    assert!(matches!(
+        validators.list(),
+        Err(VirtLintError::DuplicateRuleId(id)) if id == "common/check_only"
+    ));
+
+    std::fs::remove_dir_all(&base).unwrap();
+}
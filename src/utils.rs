@@ -2,12 +2,17 @@
 
 use crate::VirtLintError;
 use crate::VirtLintResult;
+use crate::WarningLocation;
 use libxml::tree::Document;
+use libxml::tree::Node;
+use libxml::tree::NodeType;
 use libxml::xpath::Context;
+use std::cell::Cell;
 use std::ffi::OsStr;
 use std::fs::read_dir;
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::Once;
 
 pub(crate) fn xpath_eval_or_none(doc: &Document, xpath: &str) -> Option<String> {
     let mut ret = String::new();
@@ -27,6 +32,19 @@ pub(crate) fn xpath_eval_or_none(doc: &Document, xpath: &str) -> Option<String>
     Some(ret)
 }
 
+/// Obtain name and (lowercased) UUID of the domain defined by `domxml`.
+pub(crate) fn domain_identity(domxml: &str) -> VirtLintResult<(Option<String>, Option<String>)> {
+    let parser = libxml::parser::Parser::default();
+    let doc = parser.parse_string(domxml)?;
+
+    let get = |xpath| xpath_eval_or_none(&doc, xpath).filter(|s: &String| !s.is_empty());
+
+    Ok((
+        get("/domain/name"),
+        get("/domain/uuid").map(|s| s.to_lowercase()),
+    ))
+}
+
 pub(crate) fn xpath_eval_nodeset_or_none(doc: &Document, xpath: &str) -> Option<Vec<String>> {
     let mut ret: Vec<String> = Vec::new();
 
@@ -55,6 +73,100 @@ pub(crate) fn xpath_eval_nodeset_or_none(doc: &Document, xpath: &str) -> Option<
     Some(ret)
 }
 
+/// Construct an unambiguous XPath pointing to given `node`, e.g.
+/// `/domain/devices/controller[3]/target/@chassis`.
+///
+/// Just like xmlGetNodePath(), position predicates are added only for elements which have
+/// siblings of the same name.
+fn node_xpath(node: &Node) -> String {
+    let name = node.get_name();
+
+    match node.get_type() {
+        Some(NodeType::AttributeNode) => {
+            let parent = node
+                .get_parent()
+                .map(|p| node_xpath(&p))
+                .unwrap_or_default();
+            format!("{parent}/@{name}")
+        }
+        Some(NodeType::ElementNode) => {
+            let parent = match node.get_parent() {
+                Some(p) if p.get_type() == Some(NodeType::ElementNode) => node_xpath(&p),
+                _ => String::new(),
+            };
+
+            let siblings = match node.get_parent() {
+                Some(p) => p
+                    .get_child_elements()
+                    .into_iter()
+                    .filter(|n| n.get_name() == name)
+                    .collect(),
+                None => vec![],
+            };
+
+            if siblings.len() > 1 {
+                let pos = siblings.iter().position(|n| n == node).unwrap_or_default();
+                format!("{parent}/{name}[{}]", pos + 1)
+            } else {
+                format!("{parent}/{name}")
+            }
+        }
+        _ => {
+            let parent = node
+                .get_parent()
+                .map(|p| node_xpath(&p))
+                .unwrap_or_default();
+            format!("{parent}/text()")
+        }
+    }
+}
+
+/// Find the line (as recorded by libxml) and column (1-based) of the start tag of `node`.
+///
+/// Libxml does not record columns, so the column is found by looking up the start tag on the
+/// corresponding line of the original document `xml`.
+fn node_line_column(node: &Node, xml: &str) -> (Option<u32>, Option<u32>) {
+    let elem = match node.get_type() {
+        Some(NodeType::ElementNode) => node.clone(),
+        _ => match node.get_parent() {
+            Some(p) => p,
+            None => return (None, None),
+        },
+    };
+
+    let line = unsafe { libxml::bindings::xmlGetLineNo(elem.node_ptr()) };
+    if line <= 0 {
+        return (None, None);
+    }
+
+    let column = xml
+        .lines()
+        .nth(line as usize - 1)
+        .and_then(|l| l.find(&format!("<{}", elem.get_name())))
+        .map(|c| c as u32 + 1);
+
+    (Some(line as u32), column)
+}
+
+/// Evaluate `xpath` over `doc` (parsed from `xml`) and turn each matching node into a
+/// [`WarningLocation`].
+pub(crate) fn xpath_locations(doc: &Document, xml: &str, xpath: &str) -> Vec<WarningLocation> {
+    let ctxt = Context::new(doc).unwrap();
+
+    let nodes = match ctxt.evaluate(xpath) {
+        Ok(nodes) => nodes.get_nodes_as_vec(),
+        Err(_) => return vec![],
+    };
+
+    nodes
+        .iter()
+        .map(|node| {
+            let (line, column) = node_line_column(node, xml);
+            WarningLocation::new(node_xpath(node), line, column)
+        })
+        .collect()
+}
+
 pub(crate) fn parse_int(s: &str) -> std::result::Result<u64, std::num::ParseIntError> {
     if let Some(s) = s.strip_prefix("0x") {
         u64::from_str_radix(s, 16)
@@ -67,6 +179,56 @@ pub(crate) fn parse_int(s: &str) -> std::result::Result<u64, std::num::ParseIntE
     }
 }
 
+/// Obtain size held by memory element `node` (e.g. `<memory unit='MiB'>1024</memory>`) in KiB,
+/// rounded up. Units are those of libvirt, with KiB being the default.
+pub(crate) fn memory_kib(node: &Node) -> VirtLintResult<u64> {
+    let value = parse_int(node.get_content().trim())?;
+
+    let unit: u64 = match node.get_property("unit").as_deref() {
+        None | Some("k") | Some("KiB") => return Ok(value),
+        Some("b") | Some("bytes") => 1,
+        Some("KB") => 1000,
+        Some("MB") => 1000_u64.pow(2),
+        Some("M") | Some("MiB") => 1024_u64.pow(2),
+        Some("GB") => 1000_u64.pow(3),
+        Some("G") | Some("GiB") => 1024_u64.pow(3),
+        Some("TB") => 1000_u64.pow(4),
+        Some("T") | Some("TiB") => 1024_u64.pow(4),
+        Some("PB") => 1000_u64.pow(5),
+        Some("P") | Some("PiB") => 1024_u64.pow(5),
+        Some("EB") => 1000_u64.pow(6),
+        Some("E") | Some("EiB") => 1024_u64.pow(6),
+        Some(_) => return Err(VirtLintError::InvalidArgument("Unknown memory unit")),
+    };
+
+    let bytes = value
+        .checked_mul(unit)
+        .ok_or(VirtLintError::InvalidArgument("Memory size out of range"))?;
+
+    Ok(bytes.div_ceil(1024))
+}
+
+/// Like [`memory_kib()`], but for the first element matching `xpath`. Returns `None` if there
+/// is no such element.
+pub(crate) fn xpath_memory_kib(doc: &Document, xpath: &str) -> VirtLintResult<Option<u64>> {
+    let ctxt = Context::new(doc).unwrap();
+
+    match ctxt.evaluate(xpath) {
+        Ok(nodes) => nodes.get_nodes_as_vec().first().map(memory_kib).transpose(),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Like [`memory_kib()`], but for all elements matching `xpath`.
+pub(crate) fn xpath_memories_kib(doc: &Document, xpath: &str) -> VirtLintResult<Vec<u64>> {
+    let ctxt = Context::new(doc).unwrap();
+
+    match ctxt.evaluate(xpath) {
+        Ok(nodes) => nodes.get_nodes_as_vec().iter().map(memory_kib).collect(),
+        Err(_) => Ok(Vec::new()),
+    }
+}
+
 pub(crate) fn recurse_files(
     path: impl AsRef<Path> + std::fmt::Debug,
     filename_prefix: Option<&OsStr>,
@@ -109,3 +271,40 @@ pub(crate) fn recurse_files(
 
     Ok(buf)
 }
+
+thread_local! {
+    static PANIC_SILENT: Cell<bool> = const { Cell::new(false) };
+}
+
+static PANIC_HOOK: Once = Once::new();
+
+/// Run `f`, turning a panic into an error. Used to isolate linting rules from each other.
+///
+/// The panic is reported as the error of the rule only, i.e. it is not printed to stderr. To
+/// that end, a panic hook is installed on the first call which silences panics caught here
+/// and passes others to the hook that was set before.
+pub(crate) fn catch_panic<F: FnOnce() -> VirtLintResult<()>>(f: F) -> VirtLintResult<()> {
+    PANIC_HOOK.call_once(|| {
+        let hook = std::panic::take_hook();
+
+        std::panic::set_hook(Box::new(move |info| {
+            if !PANIC_SILENT.with(Cell::get) {
+                hook(info)
+            }
+        }));
+    });
+
+    let silent = PANIC_SILENT.with(|s| s.replace(true));
+    let ret = std::panic::catch_unwind(std::panic::AssertUnwindSafe(f));
+    PANIC_SILENT.with(|s| s.set(silent));
+
+    ret.unwrap_or_else(|payload| {
+        let msg = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| String::from("unknown panic"));
+
+        Err(VirtLintError::Panic(msg))
+    })
+}
@@ -0,0 +1,144 @@
+/* SPDX-License-Identifier: LGPL-3.0-or-later */
+
+use crate::*;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// How a linting rule is implemented.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Display, PartialEq, PartialOrd, Ord, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValidatorEngine {
+    /// Built into virt-lint
+    Builtin,
+
+    /// Lua script
+    Lua,
+
+    /// Python script
+    Python,
+}
+
+/// What a linting rule needs besides domain XML.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Needs {
+    pub(crate) connection: bool,
+    pub(crate) capabilities: bool,
+    pub(crate) domain_capabilities: bool,
+}
+
+impl Needs {
+    /// Needs of a Lua or Python validator as declared in its documentation, see
+    /// [`RuleDoc::needs()`]. `None` if not declared.
+    fn from_doc(doc: &RuleDoc) -> Option<Self> {
+        let needs = doc.needs()?;
+        let has = |need: &str| needs.iter().any(|n| n == need);
+
+        Some(Self {
+            connection: has("connection"),
+            capabilities: has("capabilities"),
+            domain_capabilities: has("domain-capabilities"),
+        })
+    }
+}
+
+/// Information about a linting rule, see [`VirtLint::list_validators()`].
+#[derive(Clone, Debug, PartialEq, PartialOrd, Ord, Eq, Serialize)]
+pub struct ValidatorInfo {
+    id: String,
+    engine: ValidatorEngine,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<PathBuf>,
+    tags: Vec<String>,
+    description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    level: Option<WarningLevel>,
+    needs_connection: Option<bool>,
+    needs_capabilities: Option<bool>,
+    needs_domain_capabilities: Option<bool>,
+}
+
+impl ValidatorInfo {
+    pub(crate) fn new(
+        doc: &RuleDoc,
+        engine: ValidatorEngine,
+        level: Option<WarningLevel>,
+        needs: Option<Needs>,
+    ) -> Self {
+        Self {
+            id: doc.id().to_string(),
+            engine,
+            path: None,
+            tags: doc.tags().to_vec(),
+            description: doc.summary().to_string(),
+            level,
+            needs_connection: needs.map(|n| n.connection),
+            needs_capabilities: needs.map(|n| n.capabilities),
+            needs_domain_capabilities: needs.map(|n| n.domain_capabilities),
+        }
+    }
+
+    /// Information about Lua or Python validator at `path`. The level and needs are those
+    /// declared in its documentation, unknown if not declared.
+    pub(crate) fn from_script(doc: &RuleDoc, engine: ValidatorEngine, path: &Path) -> Self {
+        let mut info = Self::new(doc, engine, doc.level(), Needs::from_doc(doc));
+        info.path = Some(path.to_path_buf());
+        info
+    }
+
+    /// Rule ID, see [`VirtLintWarning::id()`].
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// How the rule is implemented.
+    pub fn engine(&self) -> ValidatorEngine {
+        self.engine
+    }
+
+    /// Path to the Lua or Python script, `None` for built-in rules.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Tags of the rule.
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    /// One line description of what the rule checks, see [`RuleDoc::summary()`].
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Level of warnings produced by the rule (before applying [`VirtLintConfig`]). If the rule
+    /// produces warnings of several levels, the most severe one. `None` if unknown, i.e. not
+    /// declared by a Lua or Python validator (see [`RuleDoc::level()`]).
+    pub fn level(&self) -> Option<WarningLevel> {
+        self.level
+    }
+
+    /// Whether the rule needs a connection to hypervisor. Without it, the rule is skipped.
+    /// `None` if unknown, i.e. not declared by a Lua or Python validator (see
+    /// [`RuleDoc::needs()`]).
+    pub fn needs_connection(&self) -> Option<bool> {
+        self.needs_connection
+    }
+
+    /// Whether the rule needs capabilities, obtained either from the connection or set by
+    /// [`VirtLint::capabilities_set()`]. `None` if unknown, see [`needs_connection()`].
+    ///
+    /// [`needs_connection()`]: ValidatorInfo::needs_connection
+    pub fn needs_capabilities(&self) -> Option<bool> {
+        self.needs_capabilities
+    }
+
+    /// Whether the rule needs domain capabilities, obtained either from the connection or
+    /// added by [`VirtLint::domain_capabilities_add()`]. `None` if unknown, see
+    /// [`needs_connection()`].
+    ///
+    /// [`needs_connection()`]: ValidatorInfo::needs_connection
+    pub fn needs_domain_capabilities(&self) -> Option<bool> {
+        self.needs_domain_capabilities
+    }
+}
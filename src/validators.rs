@@ -1,6 +1,8 @@
 /* SPDX-License-Identifier: LGPL-3.0-or-later */
 
+use crate::tag_expr::TagSelection;
 use crate::utils::*;
+use crate::validator_info::Needs;
 use crate::validators_lua::*;
 use crate::validators_python::*;
 use crate::*;
@@ -14,7 +16,11 @@ type ValidatorCB = dyn Fn(&mut VirtLint, &str, &Document, &Validator) -> VirtLin
 
 struct Validator {
     cb: &'static ValidatorCB,
+    id: &'static str,
     tags: HashSet<&'static str>,
+    doc: &'static str,
+    level: WarningLevel,
+    needs: Needs,
 }
 
 pub struct Validators {
@@ -24,23 +30,50 @@ pub struct Validators {
 }
 
 impl Validators {
-    fn new_paths(paths: Vec<PathBuf>) -> Self {
+    pub(crate) fn new_paths(paths: Vec<PathBuf>) -> Self {
         let validators = vec![
             Validator {
                 cb: &check_numa,
+                id: "numa.fit",
                 tags: HashSet::from(["TAG_1", "TAG_2"]),
+                doc: DOC_NUMA,
+                level: WarningLevel::Error,
+                needs: Needs {
+                    capabilities: true,
+                    ..Default::default()
+                },
             },
             Validator {
                 cb: &check_numa_free,
+                id: "numa.free",
                 tags: HashSet::from(["TAG_2"]),
+                doc: DOC_NUMA_FREE,
+                level: WarningLevel::Error,
+                needs: Needs {
+                    connection: true,
+                    capabilities: true,
+                    ..Default::default()
+                },
             },
             Validator {
                 cb: &check_node_kvm,
+                id: "node.kvm",
                 tags: HashSet::from(["TAG_1", "TAG_3"]),
+                doc: DOC_NODE_KVM,
+                level: WarningLevel::Warning,
+                needs: Needs {
+                    capabilities: true,
+                    domain_capabilities: true,
+                    ..Default::default()
+                },
             },
             Validator {
                 cb: &check_pcie_root_ports,
+                id: "pcie.root-ports.free",
                 tags: HashSet::from(["TAG_4"]),
+                doc: DOC_PCIE_ROOT_PORTS,
+                level: WarningLevel::Notice,
+                needs: Needs::default(),
             },
         ];
 
@@ -54,11 +87,14 @@ impl Validators {
         }
     }
 
-    pub fn new() -> Self {
+    /// Directories with validators. Environment variables take precedence over `config`.
+    pub fn paths(config: &VirtLintConfig) -> Vec<PathBuf> {
         let mut paths = Vec::new();
 
         if let Some(lua_paths) = std::env::var_os("VIRT_LINT_LUA_PATH") {
             paths.extend(std::env::split_paths(&lua_paths))
+        } else if !config.lua_paths().is_empty() {
+            paths.extend_from_slice(config.lua_paths());
         } else {
             paths.extend([
                 PathBuf::from("/usr/share/virt-lint/validators_lua"),
@@ -68,6 +104,8 @@ impl Validators {
 
         if let Some(python_paths) = std::env::var_os("VIRT_LINT_PYTHON_PATH") {
             paths.extend(std::env::split_paths(&python_paths));
+        } else if !config.python_paths().is_empty() {
+            paths.extend_from_slice(config.python_paths());
         } else {
             paths.extend([
                 PathBuf::from("/usr/share/virt-lint/validators_python"),
@@ -75,7 +113,11 @@ impl Validators {
             ]);
         }
 
-        Self::new_paths(paths)
+        paths
+    }
+
+    pub fn new(config: &VirtLintConfig) -> Self {
+        Self::new_paths(Self::paths(config))
     }
 
     pub fn list_tags(&mut self) -> VirtLintResult<HashSet<String>> {
@@ -93,10 +135,66 @@ impl Validators {
         Ok(tags)
     }
 
-    fn validate_tags(&mut self, tags: &[String]) -> VirtLintResult<()> {
+    /// Rule IDs have to be unique, also across engines: a Lua `foo/check_x.lua` and a Python
+    /// `foo/check_x.py` are both `foo/check_x`, which is an error.
+    fn check_ids(&self) -> VirtLintResult<()> {
+        let mut ids: HashSet<String> = self.validators.iter().map(|v| v.id.to_string()).collect();
+
+        for id in self.lua.ids()?.into_iter().chain(self.python.ids()?) {
+            if !ids.insert(id.clone()) {
+                return Err(VirtLintError::DuplicateRuleId(id));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn rule_docs(&self) -> VirtLintResult<Vec<RuleDoc>> {
+        self.check_ids()?;
+
+        let mut docs: Vec<RuleDoc> = Vec::new();
+
+        for v in &self.validators {
+            let mut tags: Vec<String> = v.tags.iter().map(|t| t.to_string()).collect();
+            tags.sort();
+
+            docs.push(RuleDoc::parse(v.id, tags, v.doc));
+        }
+
+        docs.extend(self.lua.rule_docs()?);
+        docs.extend(self.python.rule_docs()?);
+
+        Ok(docs)
+    }
+
+    pub fn list(&self) -> VirtLintResult<Vec<ValidatorInfo>> {
+        self.check_ids()?;
+
+        let mut ret: Vec<ValidatorInfo> = Vec::new();
+
+        for v in &self.validators {
+            let mut tags: Vec<String> = v.tags.iter().map(|t| t.to_string()).collect();
+            tags.sort();
+
+            let doc = RuleDoc::parse(v.id, tags, v.doc);
+            ret.push(ValidatorInfo::new(
+                &doc,
+                ValidatorEngine::Builtin,
+                Some(v.level),
+                Some(v.needs),
+            ));
+        }
+
+        ret.extend(self.lua.list()?);
+        ret.extend(self.python.list()?);
+
+        Ok(ret)
+    }
+
+    fn validate_tags(&mut self, selection: &TagSelection) -> VirtLintResult<()> {
         let known_tags: HashSet<String> = self.list_tags()?;
 
-        for tag in tags.iter() {
+        for tag in selection.tags() {
             if !known_tags.contains(tag) {
                 return Err(VirtLintError::UnknownValidatorTag(tag.to_string()));
             }
@@ -105,23 +203,11 @@ impl Validators {
         Ok(())
     }
 
-    fn get_validators(&self, tags: &[String]) -> Vec<&Validator> {
-        if tags.is_empty() {
-            self.validators.iter().collect()
-        } else {
-            /*
-             * Cannot do `.contains() on a slice of &String with &'static str
-             * hence the .iter().any(|it| it == t)
-             * Cannot do `.contains() on a hashset of &'static str with String
-             * hence the .iter().any(|t| tags.iter().any(|it| it == t)))
-             *
-             * See https://doc.rust-lang.org/std/primitive.slice.html#method.contains
-             */
-            self.validators
-                .iter()
-                .filter(|v| v.tags.iter().any(|t| tags.iter().any(|it| it == t)))
-                .collect()
-        }
+    fn get_validators(&self, selection: &TagSelection) -> Vec<&Validator> {
+        self.validators
+            .iter()
+            .filter(|v| selection.matches(&v.tags.iter().collect::<Vec<_>>()))
+            .collect()
     }
 
     pub fn validate(
@@ -133,29 +219,54 @@ impl Validators {
         let parser = Parser::default();
         let domxml_doc = parser.parse_string(domxml)?;
 
-        self.validate_tags(tags)?;
+        let selection = TagSelection::parse(tags)?;
+        self.validate_tags(&selection)?;
+        self.check_ids()?;
 
-        let validators = self.get_validators(tags);
+        vl.suppressions_set(suppressions_parse(&domxml_doc));
 
-        self.lua.validate(tags, vl, domxml, &domxml_doc)?;
+        let validators = self.get_validators(&selection);
 
-        self.python.validate(tags, vl, domxml, &domxml_doc)?;
+        self.lua.validate(&selection, vl, domxml, &domxml_doc)?;
+
+        self.python.validate(&selection, vl, domxml, &domxml_doc)?;
 
         for validator in validators.iter() {
-            (validator.cb)(vl, domxml, &domxml_doc, validator)?;
+            let mut tags: Vec<String> = validator.tags.iter().map(|t| t.to_string()).collect();
+            tags.sort();
+
+            if vl.config().disabled(validator.id, &tags) {
+                continue;
+            }
+
+            vl.rule_start(validator.id, tags, ValidatorEngine::Builtin);
+            let ret = catch_panic(|| (validator.cb)(vl, domxml, &domxml_doc, validator));
+            vl.rule_end(ret, None)?;
         }
 
         Ok(())
     }
 }
 
+const DOC_NUMA: &str = "
+Domain fits into a host NUMA node
+
+Checks whether the memory of the domain is smaller than the memory of the biggest NUMA node
+of the host.
+
+Rationale: Memory of a domain spanning several NUMA nodes is (partially) remote to its vCPUs,
+which hurts performance.
+
+References:
+- https://libvirt.org/formatdomain.html#numa-node-tuning
+";
+
 fn check_numa(
     vl: &mut VirtLint,
-    _domxml: &str,
+    domxml: &str,
     domxml_doc: &Document,
     va: &Validator,
 ) -> VirtLintResult<()> {
-    let mut numa_mems: Vec<u64> = Vec::new();
     let mut dom_mem: u64 = 0;
     let mut would_fit: bool = false;
     let parser = Parser::default();
@@ -167,18 +278,10 @@ fn check_numa(
         }
     };
 
-    let ctxt = Context::new(&caps).unwrap();
-
-    let nodes = ctxt
-        .evaluate("//capabilities/host/topology/cells/cell/memory/text()")
-        .unwrap();
+    let numa_mems = xpath_memories_kib(&caps, "//capabilities/host/topology/cells/cell/memory")?;
 
-    for node in &nodes.get_nodes_as_vec() {
-        numa_mems.push(node.get_content().parse().unwrap())
-    }
-
-    if let Some(mem) = xpath_eval_or_none(domxml_doc, "//domain/memory") {
-        dom_mem = parse_int(&mem)?
+    if let Some(mem) = xpath_memory_kib(domxml_doc, "//domain/memory")? {
+        dom_mem = mem
     }
 
     for node in numa_mems.iter() {
@@ -189,23 +292,81 @@ fn check_numa(
     }
 
     if !would_fit {
+        // Shrink domain memory so that it fits into the biggest NUMA node, i.e. to the biggest
+        // whole MiB smaller than the node. Current memory must not exceed memory, shrink it too.
+        let fit = numa_mems
+            .iter()
+            .max()
+            .map(|max| max.saturating_sub(1) / 1024 * 1024)
+            .filter(|fit| *fit > 0);
+
+        let fix = match fit {
+            Some(fit) => {
+                let mut edits = vec![XmlEdit::Replace {
+                    xpath: String::from("//domain/memory"),
+                    xml: format!("<memory unit='KiB'>{fit}</memory>"),
+                }];
+
+                if xpath_memory_kib(domxml_doc, "//domain/currentMemory")? > Some(fit) {
+                    edits.push(XmlEdit::Replace {
+                        xpath: String::from("//domain/currentMemory"),
+                        xml: format!("<currentMemory unit='KiB'>{fit}</currentMemory>"),
+                    });
+                }
+
+                Some(WarningFix::new(
+                    String::from("Shrink domain memory to fit into the biggest host NUMA node"),
+                    edits,
+                ))
+            }
+            None => None,
+        };
+
+        let mut data = WarningData::new();
+        data.insert(String::from("requested_kib"), dom_mem.into());
+        if let Some(max) = numa_mems.iter().max() {
+            data.insert(String::from("largest_node_kib"), (*max).into());
+        }
+
         vl.add_warning(
-            va.tags
-                .iter()
-                .map(|x| x.to_string())
-                .collect::<Vec<String>>(),
-            WarningDomain::Domain,
-            WarningLevel::Error,
-            String::from("Domain would not fit into any host NUMA node"),
+            VirtLintWarning::new(
+                va.tags
+                    .iter()
+                    .map(|x| x.to_string())
+                    .collect::<Vec<String>>(),
+                va.id.to_string(),
+                WarningDomain::Domain,
+                WarningLevel::Error,
+                String::from(
+                    "Domain memory ({requested_kib} KiB) would not fit into any host NUMA node",
+                ),
+                xpath_locations(domxml_doc, domxml, "//domain/memory"),
+            )
+            .with_fix(fix)
+            .with_data(data),
         );
     }
 
     Ok(())
 }
 
+const DOC_NUMA_FREE: &str = "
+Enough free memory on a host NUMA node
+
+Checks whether there is a NUMA node on the host with enough free memory to fit the whole
+domain. Unlike numa.fit, the current state of the host is considered, hence a connection is
+needed.
+
+Rationale: If no NUMA node has enough free memory, the domain either fails to start (with
+strict memory placement) or its memory is spread across NUMA nodes.
+
+References:
+- https://libvirt.org/formatdomain.html#numa-node-tuning
+";
+
 fn check_numa_free(
     vl: &mut VirtLint,
-    _domxml: &str,
+    domxml: &str,
     domxml_doc: &Document,
     va: &Validator,
 ) -> VirtLintResult<()> {
@@ -237,42 +398,68 @@ fn check_numa_free(
         numa_ids.push(node.get_content().parse().unwrap())
     }
 
+    // Libvirt reports free memory in bytes
     for node in numa_ids.iter() {
         conn.conn
             .get_cells_free_memory(*node, 1)
             .unwrap()
             .into_iter()
-            .for_each(|x| numa_mems_free.push(x));
+            .for_each(|x| numa_mems_free.push(x / 1024));
     }
 
-    if let Some(mem) = xpath_eval_or_none(domxml_doc, "//domain/memory") {
-        dom_mem = parse_int(&mem)?
+    if let Some(mem) = xpath_memory_kib(domxml_doc, "//domain/memory")? {
+        dom_mem = mem
     }
 
-    numa_mems_free.into_iter().for_each(|x| {
-        if x > dom_mem {
+    numa_mems_free.iter().for_each(|x| {
+        if *x > dom_mem {
             would_fit = true;
         }
     });
 
     if !would_fit {
+        let mut data = WarningData::new();
+        data.insert(String::from("requested_kib"), dom_mem.into());
+        data.insert(String::from("free_kib"), numa_mems_free.clone().into());
+        if let Some(max) = numa_mems_free.iter().max() {
+            data.insert(String::from("largest_free_kib"), (*max).into());
+        }
+
         vl.add_warning(
-            va.tags
-                .iter()
-                .map(|x| x.to_string())
-                .collect::<Vec<String>>(),
-            WarningDomain::Domain,
-            WarningLevel::Error,
-            String::from("Not enough free memory on any NUMA node"),
+            VirtLintWarning::new(
+                va.tags
+                    .iter()
+                    .map(|x| x.to_string())
+                    .collect::<Vec<String>>(),
+                va.id.to_string(),
+                WarningDomain::Domain,
+                WarningLevel::Error,
+                String::from("Not enough free memory ({requested_kib} KiB) on any NUMA node"),
+                xpath_locations(domxml_doc, domxml, "//domain/memory"),
+            )
+            .with_data(data),
         );
     }
 
     Ok(())
 }
 
+const DOC_NODE_KVM: &str = "
+Host provides a suitable emulator
+
+Checks whether the host has an emulator (and accelerator) matching the architecture,
+machine type and virtualization type requested by the domain.
+
+Rationale: Without a suitable emulator the domain fails to start.
+
+References:
+- https://libvirt.org/formatcaps.html
+- https://libvirt.org/formatdomaincaps.html
+";
+
 fn check_node_kvm(
     vl: &mut VirtLint,
-    _domxml: &str,
+    domxml: &str,
     domxml_doc: &Document,
     va: &Validator,
 ) -> VirtLintResult<()> {
@@ -325,26 +512,58 @@ fn check_node_kvm(
         };
 
         emit_warning = xpath_eval_or_none(&caps, &top_xpath).is_none();
+
+        // Checked against capabilities, even if there are no domain capabilities
+        vl.rule_unskip();
     }
 
     if emit_warning {
+        let mut data = WarningData::new();
+        for (name, xpath) in [
+            ("domain_type", "//domain/@type"),
+            ("arch", "//domain/os/type/@arch"),
+            ("machine", "//domain/os/type/@machine"),
+        ] {
+            let value = xpath_eval_or_none(domxml_doc, xpath).unwrap_or_default();
+            data.insert(String::from(name), value.into());
+        }
+
         vl.add_warning(
-            va.tags
-                .iter()
-                .map(|x| x.to_string())
-                .collect::<Vec<String>>(),
-            WarningDomain::Node,
-            WarningLevel::Warning,
-            String::from("No suitable emulator found"),
+            VirtLintWarning::new(
+                va.tags
+                    .iter()
+                    .map(|x| x.to_string())
+                    .collect::<Vec<String>>(),
+                va.id.to_string(),
+                WarningDomain::Node,
+                WarningLevel::Warning,
+                String::from("No suitable {domain_type} emulator found for {arch} ({machine})"),
+                xpath_locations(domxml_doc, domxml, "//domain/os/type"),
+            )
+            .with_data(data),
         );
     }
 
     Ok(())
 }
 
+const DOC_PCIE_ROOT_PORTS: &str = "
+Free PCIe root ports for hotplug
+
+Checks whether a Q35 domain has at least one pcie-root-port controller that is not occupied
+by a device.
+
+Rationale: On Q35 machine types, PCIe devices can be hotplugged only into free root ports,
+which can not be added to a running domain.
+
+References:
+- https://libvirt.org/pci-hotplug.html
+- https://libvirt.org/formatdomain.html#controllers
+";
+
 fn check_pcie_root_ports(
     vl: &mut VirtLint,
-    _domxml: &str,
+    domxml: &str,
     domxml_doc: &Document,
     va: &Validator,
 ) -> VirtLintResult<()> {
@@ -382,6 +601,8 @@ fn check_pcie_root_ports(
         pcie_chassis.push(node.get_content().parse().unwrap());
     }
 
+    let all_chassis = pcie_chassis.clone();
+
     // Firstly, remove obviously taken root ports
     if !pcie_chassis.is_empty() {
         let nodes = ctxt
@@ -407,14 +628,44 @@ fn check_pcie_root_ports(
     // TODO
 
     if pcie_chassis.is_empty() {
+        let mut locations = xpath_locations(
+            domxml_doc,
+            domxml,
+            "//domain/devices/controller[@type='pci' and @model='pcie-root-port']",
+        );
+
+        if locations.is_empty() {
+            locations = xpath_locations(domxml_doc, domxml, "//domain/devices");
+        }
+
+        let fix = WarningFix::new(
+            String::from("Add a pcie-root-port controller"),
+            vec![XmlEdit::Insert {
+                xpath: String::from("//domain/devices"),
+                xml: String::from("<controller type='pci' model='pcie-root-port'/>"),
+            }],
+        );
+
+        // No chassis is free at this point, i.e. all of them are occupied
+        let mut data = WarningData::new();
+        data.insert(String::from("occupied_chassis"), all_chassis.into());
+
         vl.add_warning(
-            va.tags
-                .iter()
-                .map(|x| x.to_string())
-                .collect::<Vec<String>>(),
-            WarningDomain::Domain,
-            WarningLevel::Notice,
-            String::from("No free PCIe root ports found, hotplug might be not possible"),
+            VirtLintWarning::new(
+                va.tags
+                    .iter()
+                    .map(|x| x.to_string())
+                    .collect::<Vec<String>>(),
+                va.id.to_string(),
+                WarningDomain::Domain,
+                WarningLevel::Notice,
+                String::from(
+                    "No free PCIe root ports found (occupied: {occupied_chassis}), hotplug might be not possible",
+                ),
+                locations,
+            )
+            .with_fix(Some(fix))
+            .with_data(data),
         );
     }
 
@@ -1,10 +1,12 @@
 /* SPDX-License-Identifier: LGPL-3.0-or-later */
 
+use crate::docs::*;
+use crate::tag_expr::TagSelection;
 use crate::utils::*;
 use crate::*;
 use libxml::tree::Document;
-use mlua::{Error, ExternalResult, Lua, UserData};
-use std::collections::HashSet;
+use mlua::{Error, ExternalResult, Lua, Table, UserData};
+use std::collections::{HashMap, HashSet};
 use std::ffi::OsString;
 use std::path::Path;
 use std::path::PathBuf;
@@ -14,17 +16,54 @@ pub struct ValidatorsLuaUserData<'a> {
     domxml: &'a str,
     domxml_doc: &'a Document,
     tags: Vec<String>,
+    id: String,
+}
+
+fn fix_from_table(fix: Table) -> Result<WarningFix, Error> {
+    let description: String = fix.get("description")?;
+    let edits: Vec<HashMap<String, String>> = fix.get("edits")?;
+
+    let edits = edits
+        .into_iter()
+        .map(XmlEdit::try_from)
+        .collect::<VirtLintResult<Vec<XmlEdit>>>()
+        .into_lua_err()?;
+
+    Ok(WarningFix::new(description, edits))
 }
 
 fn add_warning(
     _: &Lua,
     vlud: &mut ValidatorsLuaUserData,
-    (domain, level, msg): (i32, i32, String),
+    (domain, level, msg, xpath, fix, data): (
+        i32,
+        i32,
+        String,
+        Option<String>,
+        Option<Table>,
+        Option<WarningData>,
+    ),
 ) -> Result<(), Error> {
     let domain = WarningDomain::try_from(domain).into_lua_err()?;
     let level = WarningLevel::try_from(level).into_lua_err()?;
-
-    vlud.vl.add_warning(vlud.tags.clone(), domain, level, msg);
+    let locations = match xpath {
+        Some(x) => xpath_locations(vlud.domxml_doc, vlud.domxml, &x),
+        None => vec![],
+    };
+    let fix = fix.map(fix_from_table).transpose()?;
+
+    vlud.vl.add_warning(
+        VirtLintWarning::new(
+            vlud.tags.clone(),
+            vlud.id.clone(),
+            domain,
+            level,
+            msg,
+            locations,
+        )
+        .with_fix(fix)
+        .with_data(data.unwrap_or_default()),
+    );
     Ok(())
 }
 
@@ -46,6 +85,26 @@ fn caps_xpath(
     Ok(xpath_eval_nodeset_or_none(&caps_doc, &xpath))
 }
 
+fn caps_memory_kib(
+    _: &Lua,
+    vlud: &mut ValidatorsLuaUserData,
+    xpath: String,
+) -> Result<Option<Vec<u64>>, Error> {
+    let caps = match vlud.vl.capabilities_get().into_lua_err()? {
+        Some(caps) => caps,
+        None => {
+            return Ok(None);
+        }
+    };
+
+    let parser = Parser::default();
+    let caps_doc = parser.parse_string(caps).into_lua_err()?;
+
+    xpath_memories_kib(&caps_doc, &xpath)
+        .map(Some)
+        .into_lua_err()
+}
+
 fn dom_xpath(
     _: &Lua,
     vlud: &mut ValidatorsLuaUserData,
@@ -54,6 +113,14 @@ fn dom_xpath(
     Ok(xpath_eval_nodeset_or_none(vlud.domxml_doc, &xpath))
 }
 
+fn dom_memory_kib(
+    _: &Lua,
+    vlud: &mut ValidatorsLuaUserData,
+    xpath: String,
+) -> Result<Option<u64>, Error> {
+    xpath_memory_kib(vlud.domxml_doc, &xpath).into_lua_err()
+}
+
 fn domcaps_xpath(
     _: &Lua,
     vlud: &mut ValidatorsLuaUserData,
@@ -123,6 +190,14 @@ macro_rules! libvirt_wrap{
     }
 }
 
+fn param(
+    _: &Lua,
+    vlud: &mut ValidatorsLuaUserData,
+    name: String,
+) -> Result<Option<WarningValue>, Error> {
+    Ok(vlud.vl.config().rule_param(&vlud.id, &name).cloned())
+}
+
 libvirt_wrap!(
     get_cells_free_memory(start_cell: i32, max_cells: i32) -> Vec<u64>
 );
@@ -139,12 +214,15 @@ impl UserData for ValidatorsLuaUserData<'_> {
     fn add_methods<'lua, M: mlua::UserDataMethods<'lua, Self>>(methods: &mut M) {
         methods.add_method_mut("add_warning", add_warning);
         methods.add_method_mut("caps_xpath", caps_xpath);
+        methods.add_method_mut("caps_memory_kib", caps_memory_kib);
         methods.add_method_mut("dom_xpath", dom_xpath);
+        methods.add_method_mut("dom_memory_kib", dom_memory_kib);
         methods.add_method_mut("domcaps_xpath", domcaps_xpath);
         methods.add_method_mut("caps_xml", caps_xml);
         methods.add_method_mut("dom_xml", dom_xml);
         methods.add_method_mut("domcaps_xml", domcaps_xml);
         methods.add_method_mut("xpath_eval", xpath_eval);
+        methods.add_method_mut("param", param);
         methods.add_method_mut("get_cells_free_memory", get_cells_free_memory);
     }
 }
@@ -175,63 +253,30 @@ fn get_tags_for_path(prefix: &PathBuf, path: &Path) -> Vec<String> {
     ret
 }
 
-fn get_paths_for_tag(
-    prefix: &Path,
-    tag: &String,
-    filename_prefix: &OsString,
-    ext: &OsString,
-) -> VirtLintResult<Vec<PathBuf>> {
-    let path = prefix.join(tag);
-
-    if !path.is_dir() {
-        let path = path.with_extension(ext);
-        if path.exists() {
-            return Ok(vec![path]);
-        }
-    }
-
-    recurse_files(path, Some(filename_prefix), Some(ext))
-}
-
-fn get_validators(
-    prefix: &PathBuf,
-    tags: &[String],
-    filename_prefix: &OsString,
-    ext: &OsString,
-) -> Vec<PathBuf> {
-    let mut ret: HashSet<PathBuf> = HashSet::new();
-
-    if tags.is_empty() {
-        return recurse_files(prefix, Some(filename_prefix), Some(ext)).unwrap_or_default();
-    } else {
-        for tag in tags.iter() {
-            let tag_paths =
-                get_paths_for_tag(prefix, tag, filename_prefix, ext).unwrap_or_default();
-
-            for tag_path in tag_paths {
-                ret.insert(tag_path);
-            }
-        }
+fn get_id_for_path(prefix: &PathBuf, path: &Path) -> String {
+    match path.strip_prefix(prefix) {
+        Ok(p) => p.with_extension("").to_string_lossy().into_owned(),
+        Err(_) => path.to_string_lossy().into_owned(),
     }
-
-    let mut ret = ret.into_iter().collect::<Vec<PathBuf>>();
-    ret.sort();
-    ret
 }
 
 fn validate_one(
-    path: PathBuf,
+    path: &Path,
     prefix: &PathBuf,
     vl: &mut VirtLint,
     domxml: &str,
     domxml_doc: &Document,
 ) -> VirtLintResult<()> {
     let lua = Lua::new();
+    let tags = get_tags_for_path(prefix, path);
+    let id = get_id_for_path(prefix, path);
+
     let vlud = ValidatorsLuaUserData {
         vl,
         domxml,
         domxml_doc,
-        tags: get_tags_for_path(prefix, &path),
+        tags,
+        id,
     };
 
     lua.scope(|scope| {
@@ -268,16 +313,25 @@ impl ValidatorsLua {
         }
     }
 
-    pub fn list_tags(&self) -> VirtLintResult<HashSet<String>> {
-        let mut ret: HashSet<String> = HashSet::new();
+    /// Obtain all validators as (directory, path) pairs. A validator in a later directory
+    /// overrides the one with the same ID in an earlier directory, e.g. `./validators_lua`
+    /// overrides `/usr/share/virt-lint/validators_lua`. Directories that don't exist are skipped.
+    fn files(&self) -> VirtLintResult<Vec<(&PathBuf, PathBuf)>> {
+        let mut ret: Vec<(&PathBuf, PathBuf)> = Vec::new();
 
-        for p in self.prefix.iter() {
-            let rc = recurse_files(p, Some(&self.filename_prefix), Some(&self.ext))?;
-            for path in rc {
-                let tags = get_tags_for_path(p, &path);
+        for p in self.prefix.iter().filter(|p| p.is_dir()) {
+            let mut rc = recurse_files(p, Some(&self.filename_prefix), Some(&self.ext))?;
+            rc.sort();
 
-                for tag in tags {
-                    ret.insert(tag);
+            for path in rc {
+                let id = get_id_for_path(p, &path);
+                let old = ret
+                    .iter()
+                    .position(|(q, other)| get_id_for_path(q, other) == id);
+
+                match old {
+                    Some(i) => ret[i] = (p, path),
+                    None => ret.push((p, path)),
                 }
             }
         }
@@ -285,19 +339,74 @@ impl ValidatorsLua {
         Ok(ret)
     }
 
+    pub fn ids(&self) -> VirtLintResult<Vec<String>> {
+        Ok(self
+            .files()?
+            .iter()
+            .map(|(p, path)| get_id_for_path(p, path))
+            .collect())
+    }
+
+    pub fn list_tags(&self) -> VirtLintResult<HashSet<String>> {
+        let mut ret: HashSet<String> = HashSet::new();
+
+        for (p, path) in self.files()? {
+            for tag in get_tags_for_path(p, &path) {
+                ret.insert(tag);
+            }
+        }
+
+        Ok(ret)
+    }
+
+    /// Obtain path and documentation of all validators.
+    fn scripts(&self) -> VirtLintResult<Vec<(PathBuf, RuleDoc)>> {
+        let mut ret = Vec::new();
+
+        for (p, path) in self.files()? {
+            let code = std::fs::read_to_string(&path)?;
+            let doc = RuleDoc::parse(
+                &get_id_for_path(p, &path),
+                get_tags_for_path(p, &path),
+                &header_comment(&code, "--"),
+            );
+
+            ret.push((path, doc));
+        }
+
+        Ok(ret)
+    }
+
+    pub fn rule_docs(&self) -> VirtLintResult<Vec<RuleDoc>> {
+        Ok(self.scripts()?.into_iter().map(|(_, doc)| doc).collect())
+    }
+
+    pub fn list(&self) -> VirtLintResult<Vec<ValidatorInfo>> {
+        Ok(self
+            .scripts()?
+            .iter()
+            .map(|(path, doc)| ValidatorInfo::from_script(doc, ValidatorEngine::Lua, path))
+            .collect())
+    }
+
     pub fn validate(
         &self,
-        tags: &[String],
+        selection: &TagSelection,
         vl: &mut VirtLint,
         domxml: &str,
         domxml_doc: &Document,
     ) -> VirtLintResult<()> {
-        for p in self.prefix.iter() {
-            let validators = get_validators(p, tags, &self.filename_prefix, &self.ext);
-
-            for validator in validators {
-                validate_one(validator, p, vl, domxml, domxml_doc)?;
+        for (p, validator) in self.files()? {
+            let id = get_id_for_path(p, &validator);
+            let tags = get_tags_for_path(p, &validator);
+            if !selection.matches(&tags) || vl.config().disabled(&id, &tags) {
+                continue;
             }
+
+            // Start the rule first, so that a script that can't be loaded is recorded as failed
+            vl.rule_start(&id, tags, ValidatorEngine::Lua);
+            let ret = catch_panic(|| validate_one(&validator, p, vl, domxml, domxml_doc));
+            vl.rule_end(ret, Some(&validator))?;
         }
 
         Ok(())
@@ -1,12 +1,14 @@
 /* SPDX-License-Identifier: LGPL-3.0-or-later */
 
+use crate::docs::*;
+use crate::tag_expr::TagSelection;
 use crate::utils::*;
 use crate::*;
 use libxml::tree::Document;
 use pyo3::exceptions::PyAttributeError;
 use pyo3::prelude::*;
 use pyo3::types::PyDict;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::ffi::OsString;
 use std::path::Path;
 use std::path::PathBuf;
@@ -15,9 +17,11 @@ use std::path::PathBuf;
 #[derive(Clone)]
 struct ValidatorPython {
     vl: VirtLint,
+    path: PathBuf,
     code: String,
     domxml: String,
     tags: Vec<String>,
+    id: String,
 }
 
 impl ValidatorPython {
@@ -27,15 +31,18 @@ impl ValidatorPython {
         vl: &mut VirtLint,
         domxml: String,
     ) -> VirtLintResult<Self> {
-        let tags = get_tags_for_path(prefix, &path);
+        let tags = get_tags_for_path(&prefix, &path);
+        let id = get_id_for_path(&prefix, &path);
         let vl = vl.clone();
-        let code = std::fs::read_to_string(path)?;
+        let code = std::fs::read_to_string(&path)?;
 
         Ok(Self {
             vl,
+            path: path.as_ref().to_path_buf(),
             code,
             domxml,
             tags,
+            id,
         })
     }
 
@@ -46,9 +53,15 @@ impl ValidatorPython {
             let globals = PyDict::new_bound(py);
             globals.set_item("vl", Py::new(py, self.clone())?)?;
 
-            let output = py.run_bound(&self.code, Some(&globals), None);
+            // Compile with the path as file name, so that it shows in tracebacks
+            let builtins = py.import_bound("builtins")?;
+            let output = builtins
+                .call_method1("compile", (&self.code, &self.path, "exec"))
+                .and_then(|code| builtins.call_method1("exec", (code, &globals)))
+                .map(|_| ());
 
-            if let Err(ref err) = output {
+            // With keep_going, the traceback is only recorded with the failed rule
+            if let (Err(err), false) = (&output, self.vl.config().keep_going()) {
                 if let Some(tb) = err.traceback_bound(py) {
                     eprint!("{}", tb.format()?);
                 }
@@ -71,12 +84,46 @@ impl ValidatorPython {
         Ok(xpath_eval_nodeset_or_none(&doc, &xpath))
     }
 
-    fn add_warning(&mut self, domain: i32, level: i32, msg: String) -> Result<(), VirtLintError> {
+    fn dom_memory_kib(&mut self, xpath: String) -> Result<Option<u64>, VirtLintError> {
+        let parser = Parser::default();
+        let doc = parser.parse_string(&self.domxml)?;
+        xpath_memory_kib(&doc, &xpath)
+    }
+
+    #[pyo3(signature = (domain, level, msg, xpath=None, fix=None, data=None))]
+    fn add_warning(
+        &mut self,
+        domain: i32,
+        level: i32,
+        msg: String,
+        xpath: Option<String>,
+        fix: Option<Bound<'_, PyDict>>,
+        data: Option<WarningData>,
+    ) -> Result<(), VirtLintError> {
         let domain = WarningDomain::try_from(domain)?;
         let level = WarningLevel::try_from(level)?;
-
-        self.vl
-            .add_warning(self.tags.clone(), domain, level, msg);
+        let locations = match xpath {
+            Some(x) => {
+                let parser = Parser::default();
+                let doc = parser.parse_string(&self.domxml)?;
+                xpath_locations(&doc, &self.domxml, &x)
+            }
+            None => vec![],
+        };
+        let fix = fix.map(|f| fix_from_dict(&f)).transpose()?;
+
+        self.vl.add_warning(
+            VirtLintWarning::new(
+                self.tags.clone(),
+                self.id.clone(),
+                domain,
+                level,
+                msg,
+                locations,
+            )
+            .with_fix(fix)
+            .with_data(data.unwrap_or_default()),
+        );
 
         Ok(())
     }
@@ -95,6 +142,20 @@ impl ValidatorPython {
         Ok(xpath_eval_nodeset_or_none(&caps_doc, &xpath))
     }
 
+    fn caps_memory_kib(&mut self, xpath: String) -> Result<Option<Vec<u64>>, VirtLintError> {
+        let caps = match self.vl.capabilities_get()? {
+            Some(caps) => caps,
+            None => {
+                return Ok(None);
+            }
+        };
+
+        let parser = Parser::default();
+        let caps_doc = parser.parse_string(caps)?;
+
+        xpath_memories_kib(&caps_doc, &xpath).map(Some)
+    }
+
     fn domcaps_xpath(&mut self, xpath: String) -> Result<Option<Vec<String>>, VirtLintError> {
         let parser = Parser::default();
         let dom_doc = parser.parse_string(&self.domxml)?;
@@ -111,6 +172,10 @@ impl ValidatorPython {
         Ok(xpath_eval_nodeset_or_none(&domcaps_doc, &xpath))
     }
 
+    fn param(&self, name: &str) -> Option<WarningValue> {
+        self.vl.config().rule_param(&self.id, name).cloned()
+    }
+
     fn get_libvirt_conn(&mut self, py: Python) -> PyResult<Option<PyObject>> {
         let conn = match self
             .vl
@@ -146,6 +211,24 @@ impl ValidatorPython {
     }
 }
 
+fn fix_from_dict(fix: &Bound<'_, PyDict>) -> PyResult<WarningFix> {
+    let description: String = match fix.get_item("description")? {
+        Some(x) => x.extract()?,
+        None => String::new(),
+    };
+    let edits: Vec<HashMap<String, String>> = match fix.get_item("edits")? {
+        Some(x) => x.extract()?,
+        None => vec![],
+    };
+
+    let edits = edits
+        .into_iter()
+        .map(XmlEdit::try_from)
+        .collect::<VirtLintResult<Vec<XmlEdit>>>()?;
+
+    Ok(WarningFix::new(description, edits))
+}
+
 fn get_tags_for_path<P: AsRef<Path>>(prefix: P, path: impl AsRef<Path>) -> Vec<String> {
     let mut ret = Vec::new();
 
@@ -172,48 +255,11 @@ fn get_tags_for_path<P: AsRef<Path>>(prefix: P, path: impl AsRef<Path>) -> Vec<S
     ret
 }
 
-fn get_paths_for_tag(
-    prefix: impl AsRef<Path>,
-    tag: &String,
-    filename_prefix: &OsString,
-    ext: &OsString,
-) -> VirtLintResult<Vec<PathBuf>> {
-    let path = prefix.as_ref().join(tag);
-
-    if !path.is_dir() {
-        let path = path.with_extension(ext);
-        if path.exists() {
-            return Ok(vec![path]);
-        }
-    }
-
-    recurse_files(path, Some(filename_prefix), Some(ext))
-}
-
-fn get_validators(
-    prefix: &PathBuf,
-    tags: &[String],
-    filename_prefix: &OsString,
-    ext: &OsString,
-) -> Vec<PathBuf> {
-    let mut ret: HashSet<PathBuf> = HashSet::new();
-
-    if tags.is_empty() {
-        return recurse_files(prefix, Some(filename_prefix), Some(ext)).unwrap_or_default();
-    } else {
-        for tag in tags.iter() {
-            let tag_paths =
-                get_paths_for_tag(prefix, tag, filename_prefix, ext).unwrap_or_default();
-
-            for tag_path in tag_paths {
-                ret.insert(tag_path);
-            }
-        }
+fn get_id_for_path<P: AsRef<Path>>(prefix: P, path: impl AsRef<Path>) -> String {
+    match path.as_ref().strip_prefix(prefix.as_ref()) {
+        Ok(p) => p.with_extension("").to_string_lossy().into_owned(),
+        Err(_) => path.as_ref().to_string_lossy().into_owned(),
     }
-
-    let mut ret = ret.into_iter().collect::<Vec<PathBuf>>();
-    ret.sort();
-    ret
 }
 
 pub struct ValidatorsPython {
@@ -239,16 +285,25 @@ impl ValidatorsPython {
         }
     }
 
-    pub fn list_tags(&self) -> VirtLintResult<HashSet<String>> {
-        let mut ret: HashSet<String> = HashSet::new();
+    /// Obtain all validators as (directory, path) pairs. A validator in a later directory
+    /// overrides the one with the same ID in an earlier directory, e.g. `./validators_lua`
+    /// overrides `/usr/share/virt-lint/validators_lua`. Directories that don't exist are skipped.
+    fn files(&self) -> VirtLintResult<Vec<(&PathBuf, PathBuf)>> {
+        let mut ret: Vec<(&PathBuf, PathBuf)> = Vec::new();
 
-        for p in self.prefix.iter() {
-            let rc = recurse_files(p, Some(&self.filename_prefix), Some(&self.ext))?;
-            for path in rc {
-                let tags = get_tags_for_path(p, &path);
+        for p in self.prefix.iter().filter(|p| p.is_dir()) {
+            let mut rc = recurse_files(p, Some(&self.filename_prefix), Some(&self.ext))?;
+            rc.sort();
 
-                for tag in tags {
-                    ret.insert(tag);
+            for path in rc {
+                let id = get_id_for_path(p, &path);
+                let old = ret
+                    .iter()
+                    .position(|(q, other)| get_id_for_path(q, other) == id);
+
+                match old {
+                    Some(i) => ret[i] = (p, path),
+                    None => ret.push((p, path)),
                 }
             }
         }
@@ -256,19 +311,75 @@ impl ValidatorsPython {
         Ok(ret)
     }
 
+    pub fn ids(&self) -> VirtLintResult<Vec<String>> {
+        Ok(self
+            .files()?
+            .iter()
+            .map(|(p, path)| get_id_for_path(p, path))
+            .collect())
+    }
+
+    pub fn list_tags(&self) -> VirtLintResult<HashSet<String>> {
+        let mut ret: HashSet<String> = HashSet::new();
+
+        for (p, path) in self.files()? {
+            for tag in get_tags_for_path(p, &path) {
+                ret.insert(tag);
+            }
+        }
+
+        Ok(ret)
+    }
+
+    /// Obtain path and documentation of all validators.
+    fn scripts(&self) -> VirtLintResult<Vec<(PathBuf, RuleDoc)>> {
+        let mut ret = Vec::new();
+
+        for (p, path) in self.files()? {
+            let code = std::fs::read_to_string(&path)?;
+            let doc = RuleDoc::parse(
+                &get_id_for_path(p, &path),
+                get_tags_for_path(p, &path),
+                &python_docstring(&code),
+            );
+
+            ret.push((path, doc));
+        }
+
+        Ok(ret)
+    }
+
+    pub fn rule_docs(&self) -> VirtLintResult<Vec<RuleDoc>> {
+        Ok(self.scripts()?.into_iter().map(|(_, doc)| doc).collect())
+    }
+
+    pub fn list(&self) -> VirtLintResult<Vec<ValidatorInfo>> {
+        Ok(self
+            .scripts()?
+            .iter()
+            .map(|(path, doc)| ValidatorInfo::from_script(doc, ValidatorEngine::Python, path))
+            .collect())
+    }
+
     pub fn validate(
         &self,
-        tags: &[String],
+        selection: &TagSelection,
         vl: &mut VirtLint,
         domxml: &str,
         _domxml_doc: &Document,
     ) -> VirtLintResult<()> {
-        for p in self.prefix.iter() {
-            let paths = get_validators(p, tags, &self.filename_prefix, &self.ext);
-
-            for path in paths {
-                ValidatorPython::from_path(path, p, vl, domxml.to_string())?.validate()?;
+        for (p, path) in self.files()? {
+            let id = get_id_for_path(p, &path);
+            let tags = get_tags_for_path(p, &path);
+            if !selection.matches(&tags) || vl.config().disabled(&id, &tags) {
+                continue;
             }
+
+            // Start the rule first, so that a script that can't be read is recorded as failed
+            vl.rule_start(&id, tags, ValidatorEngine::Python);
+            let ret = ValidatorPython::from_path(&path, p, vl, domxml.to_string())
+                .and_then(|mut validator| catch_panic(|| validator.validate()));
+            vl.rule_end(ret, Some(&path))?;
         }
 
         Ok(())
@@ -1,32 +1,55 @@
 /* SPDX-License-Identifier: LGPL-3.0-or-later */
 
+mod baseline;
 #[cfg(feature = "capi")]
 mod capi;
 mod caps_cache;
+mod catalog;
+mod config;
+mod docs;
 pub mod errors;
+mod fixes;
 mod helpers;
+mod report;
+mod suppressions;
+mod tag_expr;
 #[cfg(test)]
 mod tests;
 mod utils;
+mod validator_info;
 mod validators;
 mod validators_lua;
 mod validators_python;
 
+pub use crate::baseline::{Baseline, BaselineEntry};
 use crate::caps_cache::*;
+pub use crate::catalog::Catalog;
+pub use crate::config::{RuleSetting, VirtLintConfig};
+pub use crate::docs::RuleDoc;
 use crate::errors::*;
+use crate::report::LintRun;
+pub use crate::report::{LintInput, LintReport, RuleError, RuleOutcome, RuleRun};
+pub use crate::suppressions::METADATA_NAMESPACE;
+use crate::suppressions::*;
 use crate::utils::*;
+pub use crate::validator_info::{ValidatorEngine, ValidatorInfo};
 use crate::validators::*;
 use libxml::parser::Parser;
 use libxml::tree::Document;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use virt::connect::Connect;
+use virt::domain::Domain;
 
 #[macro_use]
 extern crate enum_display_derive;
 use std::fmt::Display;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use std::time::{Instant, SystemTime};
 
 #[repr(C)]
-#[derive(Clone, Copy, Debug, Display, PartialEq, PartialOrd, Ord, Eq)]
+#[derive(Clone, Copy, Debug, Display, PartialEq, PartialOrd, Ord, Eq, Serialize)]
 pub enum WarningDomain {
     /// The problem lies inside of domain XML
     Domain,
@@ -36,7 +59,7 @@ pub enum WarningDomain {
 }
 
 #[repr(C)]
-#[derive(Clone, Copy, Debug, Display, PartialEq, PartialOrd, Ord, Eq)]
+#[derive(Clone, Copy, Debug, Display, PartialEq, PartialOrd, Ord, Eq, Serialize)]
 pub enum WarningLevel {
     /// Critical error, domain won't start
     Error,
@@ -48,27 +71,237 @@ pub enum WarningLevel {
     Notice,
 }
 
-#[derive(Clone, Debug, PartialEq, PartialOrd, Ord, Eq)]
+#[derive(Clone, Debug, PartialEq, PartialOrd, Ord, Eq, Serialize)]
+pub struct WarningLocation {
+    xpath: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    line: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    column: Option<u32>,
+}
+
+impl WarningLocation {
+    fn new(xpath: String, line: Option<u32>, column: Option<u32>) -> Self {
+        Self {
+            xpath,
+            line,
+            column,
+        }
+    }
+
+    /// XPath of the offending node, e.g. `/domain/devices/controller[3]`.
+    pub fn xpath(&self) -> &str {
+        &self.xpath
+    }
+
+    /// Line of the offending node within the domain XML (starting from 1), if known.
+    pub fn line(&self) -> Option<u32> {
+        self.line
+    }
+
+    /// Column of the offending node within the domain XML (starting from 1), if known.
+    pub fn column(&self) -> Option<u32> {
+        self.column
+    }
+}
+
+impl Display for WarningLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.xpath)?;
+        if let Some(line) = self.line {
+            write!(f, ":{line}")?;
+            if let Some(column) = self.column {
+                write!(f, ":{column}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A single edit of domain XML.
+///
+/// Each edit is applied to all nodes matching `xpath`.
+#[derive(Clone, Debug, PartialEq, PartialOrd, Ord, Eq, Serialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum XmlEdit {
+    /// Insert `xml` as the last child of the matching element(s)
+    Insert { xpath: String, xml: String },
+
+    /// Replace the matching node(s) with `xml`. For attributes, `xml` is the new value.
+    Replace { xpath: String, xml: String },
+
+    /// Remove the matching node(s)
+    Remove { xpath: String },
+}
+
+/// A machine-applicable fix for a warning.
+///
+/// See [`VirtLint::apply_fixes()`].
+#[derive(Clone, Debug, PartialEq, PartialOrd, Ord, Eq, Serialize)]
+pub struct WarningFix {
+    description: String,
+    edits: Vec<XmlEdit>,
+}
+
+impl WarningFix {
+    pub fn new(description: String, edits: Vec<XmlEdit>) -> Self {
+        Self { description, edits }
+    }
+
+    /// Human readable description of the fix, e.g. "Add a pcie-root-port controller".
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Edits to domain XML, in the order they are applied.
+    pub fn edits(&self) -> &[XmlEdit] {
+        &self.edits
+    }
+}
+
+/// A value in warning data, see [`VirtLintWarning::data()`].
+#[derive(Clone, Debug, PartialEq, PartialOrd, Ord, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum WarningValue {
+    Bool(bool),
+    Int(i64),
+    String(String),
+    List(Vec<WarningValue>),
+}
+
+impl Display for WarningValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WarningValue::Bool(x) => write!(f, "{x}"),
+            WarningValue::Int(x) => write!(f, "{x}"),
+            WarningValue::String(x) => write!(f, "{x}"),
+            WarningValue::List(x) => {
+                let items: Vec<String> = x.iter().map(|i| i.to_string()).collect();
+                write!(f, "[{}]", items.join(", "))
+            }
+        }
+    }
+}
+
+/// Data attached to a warning, keyed by name.
+pub type WarningData = BTreeMap<String, WarningValue>;
+
+#[derive(Clone, Debug, PartialEq, PartialOrd, Ord, Eq, Serialize)]
 pub struct VirtLintWarning {
     tags: Vec<String>,
+    id: String,
     domain: WarningDomain,
     level: WarningLevel,
     msg: String,
+    template: String,
+    locations: Vec<WarningLocation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fix: Option<WarningFix>,
+    data: WarningData,
 }
 
 impl VirtLintWarning {
-    fn new(tags: Vec<String>, domain: WarningDomain, level: WarningLevel, msg: String) -> Self {
+    fn new(
+        tags: Vec<String>,
+        id: String,
+        domain: WarningDomain,
+        level: WarningLevel,
+        msg: String,
+        locations: Vec<WarningLocation>,
+    ) -> Self {
         Self {
             tags,
+            id,
             domain,
             level,
+            template: msg.clone(),
             msg,
+            locations,
+            fix: None,
+            data: WarningData::new(),
         }
     }
 
+    fn with_fix(mut self, fix: Option<WarningFix>) -> Self {
+        self.fix = fix;
+        self
+    }
+
+    fn with_data(mut self, data: WarningData) -> Self {
+        self.msg = catalog::render(&self.template, &data);
+        self.data = data;
+        self
+    }
+
     pub fn get(&self) -> (&[String], &WarningDomain, &WarningLevel, &String) {
         (&self.tags, &self.domain, &self.level, &self.msg)
     }
+
+    /// Obtain the untranslated message template.
+    ///
+    /// The template may contain placeholders (e.g. `{requested_kib}`) which are replaced with
+    /// values from [`data()`] in the message returned by [`get()`]. Only placeholders naming a
+    /// key of [`data()`] are replaced. The message is also translated, see
+    /// [`VirtLint::catalog_set()`].
+    ///
+    /// [`data()`]: VirtLintWarning::data
+    /// [`get()`]: VirtLintWarning::get
+    pub fn template(&self) -> &str {
+        &self.template
+    }
+
+    /// Obtain the rule ID.
+    ///
+    /// Each linting rule has a stable and unique identifier (e.g. `numa.fit` for built-in rules,
+    /// or `common/check_numa` for a Lua/Python validator). Unlike the message, the ID does not
+    /// change when the warning text is reworded.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Obtain locations within domain XML the warning relates to.
+    ///
+    /// Might be empty, e.g. if the problem lies on the host rather than in the domain XML.
+    pub fn locations(&self) -> &[WarningLocation] {
+        &self.locations
+    }
+
+    /// Obtain suggested fix, if any.
+    ///
+    /// See [`VirtLint::apply_fixes()`].
+    pub fn fix(&self) -> Option<&WarningFix> {
+        self.fix.as_ref()
+    }
+
+    /// Obtain data the rule computed, e.g. requested and available memory.
+    ///
+    /// Keys are specific to each rule, but stable. Unlike the message, they are meant to be
+    /// consumed by programs.
+    pub fn data(&self) -> &WarningData {
+        &self.data
+    }
+}
+
+/// A warning waived in domain XML.
+///
+/// See [`VirtLint::suppressed()`].
+#[derive(Clone, Debug, PartialEq, PartialOrd, Ord, Eq, Serialize)]
+pub struct SuppressedWarning {
+    warning: VirtLintWarning,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+}
+
+impl SuppressedWarning {
+    /// The warning that would have been reported.
+    pub fn warning(&self) -> &VirtLintWarning {
+        &self.warning
+    }
+
+    /// Reason for the suppression, as recorded in domain XML.
+    pub fn reason(&self) -> Option<&str> {
+        self.reason.as_deref()
+    }
 }
 
 // Connect does not implement Copy trait. Use Clone with automatic refcounting for now.
@@ -95,7 +328,15 @@ pub struct VirtLint {
     caps_cache: Arc<Mutex<CapsCache>>,
     domcaps_cache: Arc<Mutex<DomCapsCache>>,
     warnings: Arc<Mutex<Vec<VirtLintWarning>>>,
+    suppressed: Arc<Mutex<Vec<SuppressedWarning>>>,
+    rules: Arc<Mutex<Vec<RuleRun>>>,
+    suppressions: Vec<Suppression>,
     error_on_no_connect: bool,
+    config: VirtLintConfig,
+    baseline: Option<Baseline>,
+    baseline_stale: Vec<BaselineEntry>,
+    catalog: Option<Arc<Catalog>>,
+    last_run: Option<LintRun>,
 }
 
 impl VirtLint {
@@ -125,8 +366,51 @@ impl VirtLint {
             caps_cache: Arc::new(Mutex::new(CapsCache::new())),
             domcaps_cache: Arc::new(Mutex::new(DomCapsCache::new())),
             warnings: Arc::new(Mutex::new(Vec::new())),
+            suppressed: Arc::new(Mutex::new(Vec::new())),
+            rules: Arc::new(Mutex::new(Vec::new())),
+            suppressions: Vec::new(),
+            error_on_no_connect: false,
+            config: VirtLintConfig::new(),
+            baseline: None,
+            baseline_stale: Vec::new(),
+            // Missing or broken catalog is not fatal, messages are just not translated
+            catalog: Catalog::find(None).ok().flatten().map(Arc::new),
+            last_run: None,
+        }
+    }
+
+    /// Creates a VirtLint object sharing the connection, (domain) capabilities, configuration,
+    /// baseline and message catalog with `self`, but with its own validation results.
+    ///
+    /// Use this to validate several domain XMLs in parallel, e.g. one fork per thread, while
+    /// fetching capabilities only once.
+    pub fn fork(&self) -> Self {
+        Self {
+            conn: self.conn.clone(),
+            caps_cache: self.caps_cache.clone(),
+            domcaps_cache: self.domcaps_cache.clone(),
+            warnings: Arc::new(Mutex::new(Vec::new())),
+            suppressed: Arc::new(Mutex::new(Vec::new())),
+            rules: Arc::new(Mutex::new(Vec::new())),
+            suppressions: Vec::new(),
             error_on_no_connect: false,
+            config: self.config.clone(),
+            baseline: self.baseline.clone(),
+            baseline_stale: Vec::new(),
+            catalog: self.catalog.clone(),
+            last_run: None,
+        }
+    }
+
+    /// Mark the currently executed rule as needing the connection that is missing and return the
+    /// error to fail it with. See [`validate()`].
+    ///
+    /// [`validate()`]: VirtLint::validate
+    fn no_connect_error(&mut self) -> VirtLintError {
+        if let Some(rule) = self.rules.lock().expect("Mutex poisoned").last_mut() {
+            rule.set_no_connect();
         }
+        VirtLintError::InvalidArgument("no connection")
     }
 
     /// Obtain the connection.
@@ -134,7 +418,10 @@ impl VirtLint {
     /// Intended to be used by validators.
     fn get_conn(&mut self) -> VirtLintResult<Option<VirtLintConnect>> {
         if self.conn.is_none() && self.error_on_no_connect {
-            return Err(VirtLintError::InvalidArgument("no connection"));
+            return Err(self.no_connect_error());
+        }
+        if self.conn.is_none() {
+            self.rule_skip("no connection");
         }
         Ok(self.conn.clone())
     }
@@ -144,7 +431,10 @@ impl VirtLint {
     /// Intended to be used by validators.
     fn get_virt_conn(&mut self) -> VirtLintResult<Option<Connect>> {
         if self.conn.is_none() && self.error_on_no_connect {
-            return Err(VirtLintError::InvalidArgument("no connection"));
+            return Err(self.no_connect_error());
+        }
+        if self.conn.is_none() {
+            self.rule_skip("no connection");
         }
         Ok(self.conn.as_ref().map(|c| c.conn.clone()))
     }
@@ -155,12 +445,24 @@ impl VirtLint {
     /// If the offline mode was requested and no capabilities were set beforehand (via
     /// [`capabilities_set()`]) an error is returned.
     fn capabilities_get(&mut self) -> VirtLintResult<Option<String>> {
-        Ok(self
+        let caps = self
             .caps_cache
             .lock()
             .expect("Mutex poisoned")
-            .get(self.conn.as_ref(), self.error_on_no_connect)?
-            .map(String::to_string))
+            .get(self.conn.as_ref(), self.error_on_no_connect)
+            .map(|caps| caps.map(String::to_string));
+
+        // The only InvalidArgument the cache returns is for missing connection
+        let caps = match caps {
+            Err(VirtLintError::InvalidArgument(_)) => return Err(self.no_connect_error()),
+            caps => caps?,
+        };
+
+        if caps.is_none() {
+            self.rule_skip("no connection and no capabilities");
+        }
+
+        Ok(caps)
     }
 
     /// Set capabilities.
@@ -200,6 +502,17 @@ impl VirtLint {
             virttype = xpath_eval_or_none(domxml_doc, "//domain/@type");
         }
 
+        // Describe what domain capabilities were looked for, in case there are none
+        let key: Vec<String> = [
+            ("arch", &arch),
+            ("machine", &machine),
+            ("type", &virttype),
+            ("emulator", &emulator),
+        ]
+        .iter()
+        .filter_map(|(what, val)| val.as_ref().map(|v| format!("{what} {v}")))
+        .collect();
+
         let mut cache = self.domcaps_cache.lock().expect("Mutex poisoned");
 
         let caps = cache
@@ -210,8 +523,25 @@ impl VirtLint {
                 arch,
                 machine,
                 virttype,
-            )?
-            .map(String::to_string);
+            )
+            .map(|caps| caps.map(String::to_string));
+        drop(cache);
+
+        let caps = match caps {
+            Err(VirtLintError::InvalidArgument(_)) => return Err(self.no_connect_error()),
+            caps => caps?,
+        };
+
+        if caps.is_none() {
+            if key.is_empty() {
+                self.rule_skip("no connection and no matching domain capabilities");
+            } else {
+                self.rule_skip(&format!(
+                    "no connection and no domain capabilities for {}",
+                    key.join(", ")
+                ));
+            }
+        }
 
         Ok(caps)
     }
@@ -237,24 +567,170 @@ impl VirtLint {
             .add(domcapsxml)
     }
 
+    /// Set lint configuration.
+    ///
+    /// The configuration is applied onto all warnings produced by subsequent [`validate()`]
+    /// calls, e.g. to override their level or to drop them completely.
+    ///
+    /// [`validate()`]: VirtLint::validate
+    pub fn config_set(&mut self, config: VirtLintConfig) {
+        self.config = config;
+    }
+
+    /// Get lint configuration.
+    pub fn config(&self) -> &VirtLintConfig {
+        &self.config
+    }
+
+    /// Set baseline of known warnings.
+    ///
+    /// Subsequent [`validate()`] calls report only warnings not recorded in `baseline`, while
+    /// baseline entries that no longer occur are reported by [`baseline_stale()`]. Pass `None`
+    /// to report all warnings.
+    ///
+    /// [`validate()`]: VirtLint::validate
+    /// [`baseline_stale()`]: VirtLint::baseline_stale
+    pub fn baseline_set(&mut self, baseline: Option<Baseline>) {
+        self.baseline = baseline;
+    }
+
+    /// Get baseline of known warnings.
+    pub fn baseline(&self) -> Option<&Baseline> {
+        self.baseline.as_ref()
+    }
+
+    /// Obtain baseline entries for the validated domain that no longer occur.
+    ///
+    /// Only entries of rules that ran are reported, not of those that were not selected, were
+    /// skipped or failed (see [`rules()`]). See [`baseline_set()`].
+    ///
+    /// [`baseline_set()`]: VirtLint::baseline_set
+    /// [`rules()`]: VirtLint::rules
+    pub fn baseline_stale(&self) -> &[BaselineEntry] {
+        &self.baseline_stale
+    }
+
+    /// Set message catalog.
+    ///
+    /// Warning messages produced by subsequent [`validate()`] calls are translated using
+    /// `catalog`. Pass `None` to keep messages untranslated. By default, the catalog for the
+    /// locale of the environment is used (see [`Catalog::find()`]).
+    ///
+    /// [`validate()`]: VirtLint::validate
+    pub fn catalog_set(&mut self, catalog: Option<Catalog>) {
+        self.catalog = catalog.map(Arc::new);
+    }
+
+    /// Set locale of warning messages.
+    ///
+    /// Find catalog for `locale` (e.g. `cs_CZ.UTF-8`) and use it, see [`catalog_set()`]. If
+    /// `locale` is `None` then it is obtained from the environment. For the "C" locale, or a
+    /// locale with no catalog, messages are not translated.
+    ///
+    /// [`catalog_set()`]: VirtLint::catalog_set
+    pub fn locale_set(&mut self, locale: Option<&str>) -> VirtLintResult<()> {
+        self.catalog_set(Catalog::find(locale)?);
+        Ok(())
+    }
+
     /// Add new warning
     ///
     /// Intended to be used by validators.
-    fn add_warning(
-        &mut self,
-        tags: Vec<String>,
-        domain: WarningDomain,
-        level: WarningLevel,
-        msg: String,
-    ) {
-        let mut tags = tags.clone();
-        tags.sort();
+    fn add_warning(&mut self, w: VirtLintWarning) {
+        let mut w = w;
+        w.tags.sort();
+
+        if let Some(rule) = self.rules.lock().expect("Mutex poisoned").last_mut() {
+            rule.warning_added();
+        }
+
+        let template = self
+            .catalog
+            .as_ref()
+            .and_then(|c| c.translate(&w.id, &w.template))
+            .unwrap_or(&w.template);
+        w.msg = catalog::render(template, &w.data);
+
+        let w = match self.config.apply(w) {
+            Some(w) => w,
+            None => return,
+        };
+
+        if let Some(s) = self.suppressions.iter().find(|s| s.matches(&w)) {
+            let reason = s.reason().map(str::to_string);
+
+            self.suppressed
+                .lock()
+                .expect("Mutex poisoned")
+                .push(SuppressedWarning { warning: w, reason });
+            return;
+        }
 
-        let w = VirtLintWarning::new(tags, domain, level, msg);
+        if let Some(rule) = self.rules.lock().expect("Mutex poisoned").last_mut() {
+            rule.warning_count_set(rule.warning_count() + 1);
+        }
 
         self.warnings.lock().expect("Mutex poisoned").push(w);
     }
 
+    /// Record that a rule is about to be executed.
+    ///
+    /// Intended to be used by validators.
+    fn rule_start(&mut self, id: &str, tags: Vec<String>, engine: ValidatorEngine) {
+        self.rules
+            .lock()
+            .expect("Mutex poisoned")
+            .push(RuleRun::new(id, tags, engine));
+    }
+
+    /// Mark the currently executed rule as skipped.
+    fn rule_skip(&mut self, reason: &str) {
+        if let Some(rule) = self.rules.lock().expect("Mutex poisoned").last_mut() {
+            rule.skip(reason);
+        }
+    }
+
+    /// Mark the currently executed rule as run after all, e.g. when it checked the domain using
+    /// other information than the one that was not available.
+    ///
+    /// Intended to be used by validators.
+    fn rule_unskip(&mut self) {
+        if let Some(rule) = self.rules.lock().expect("Mutex poisoned").last_mut() {
+            rule.unskip();
+        }
+    }
+
+    /// Record result of the currently executed rule, implemented by script at `path` (if any).
+    /// If it is an error the rule is marked as failed. The error is then passed through, unless
+    /// [`VirtLintConfig::keep_going()`] is set and the rule did not fail for missing connection.
+    ///
+    /// Intended to be used by validators.
+    fn rule_end(&mut self, ret: VirtLintResult<()>, path: Option<&Path>) -> VirtLintResult<()> {
+        let mut no_connect = false;
+
+        if let Some(rule) = self.rules.lock().expect("Mutex poisoned").last_mut() {
+            rule.finish();
+            no_connect = rule.no_connect();
+
+            if let Err(e) = &ret {
+                rule.fail(RuleError::new(e, path));
+            }
+        }
+
+        if self.config.keep_going() && !no_connect {
+            return Ok(());
+        }
+
+        ret
+    }
+
+    /// Set suppressions found in domain XML.
+    ///
+    /// Intended to be used by validators, before any rule is run.
+    fn suppressions_set(&mut self, suppressions: Vec<Suppression>) {
+        self.suppressions = suppressions;
+    }
+
     /// Validate given domain XML against set of internal rules.
     ///
     /// Execute internal validators (linting rules) attempting to find problems with domain
@@ -264,19 +740,50 @@ impl VirtLint {
     ///
     /// The linting warnings can be then obtained via [`warnings()`].
     ///
+    /// Rules can be waived in the domain XML itself, by listing them (either rule ID or tag) in
+    /// `<metadata/>` under [`METADATA_NAMESPACE`]:
+    ///
+    /// ```xml
+    /// <metadata>
+    ///   <vl:lint xmlns:vl="https://gitlab.com/MichalPrivoznik/virt-lint/xmlns/1.0">
+    ///     <vl:ignore rule="numa.fit" reason="Spans NUMA nodes on purpose"/>
+    ///   </vl:lint>
+    /// </metadata>
+    /// ```
+    ///
+    /// Warnings produced by such rules are not reported by [`warnings()`] but by
+    /// [`suppressed()`], along with the reason.
+    ///
+    /// Warnings recorded in baseline (see [`baseline_set()`]) are not reported either.
+    ///
+    /// All results of the run can be also obtained at once via [`report()`].
+    ///
     /// Each linting rule has a tag associated with it. To validate domain XML against just a
     /// subset of rules, pass vector of selected tags in `validator_tags`. To obtain the list of
     /// all possible tags use [`list_validator_tags()`]. If no tags are specified then all linting
-    /// rules are executed.
+    /// rules are executed, otherwise those with any of the tags.
+    ///
+    /// Each item of `validator_tags` can also be an expression over tags using `and`, `or`,
+    /// `not` and parentheses, e.g. `common and not common/check_numa_free`. Rules matching any
+    /// of the expressions are executed.
     ///
     /// For offline mode validation (i.e. no connection was provided in [`new()`]), use
     /// `error_on_no_connect` to either skip linting rules that require connection (`false`), or
     /// make the whole validation fail (`true`).
     ///
+    /// A rule that fails (e.g. a Lua error or a Python exception in a validator) makes the
+    /// whole validation fail too, unless [`VirtLintConfig::keep_going()`] is set, in which case
+    /// the failure is recorded (see [`rules()`]) and other rules are run. A rule failing for
+    /// missing connection with `error_on_no_connect` set fails the validation regardless.
+    ///
     /// [`new()`]: VirtLint::new
     /// [`capabilities_set()`]: VirtLint::capabilities_set
     /// [`domain_capabilities_add()`]: VirtLint::domain_capabilities_add
     /// [`warnings()`]: VirtLint::warnings
+    /// [`suppressed()`]: VirtLint::suppressed
+    /// [`baseline_set()`]: VirtLint::baseline_set
+    /// [`report()`]: VirtLint::report
+    /// [`rules()`]: VirtLint::rules
     /// [`list_validator_tags()`]: VirtLint::list_validator_tags
     pub fn validate(
         &mut self,
@@ -284,14 +791,60 @@ impl VirtLint {
         validator_tags: &[String],
         error_on_no_connect: bool,
     ) -> VirtLintResult<()> {
-        let mut validators = Validators::new();
+        let mut validators = Validators::new(&self.config);
 
         // Clear warnings from previous runs
         self.warnings.lock().expect("Mutex poisoned").clear();
+        self.suppressed.lock().expect("Mutex poisoned").clear();
+        self.rules.lock().expect("Mutex poisoned").clear();
+        self.baseline_stale.clear();
+        self.last_run = None;
 
         self.error_on_no_connect = error_on_no_connect;
 
-        validators.validate(validator_tags, self, domxml)
+        let started = SystemTime::now();
+        let start = Instant::now();
+
+        validators.validate(validator_tags, self, domxml)?;
+
+        if let Some(baseline) = &self.baseline {
+            let mut warnings = self.warnings.lock().expect("Mutex poisoned");
+            let mut rules = self.rules.lock().expect("Mutex poisoned");
+            self.baseline_stale = baseline.apply(domxml, &mut warnings, &rules)?;
+
+            // Warnings recorded in baseline are not reported, hence not counted either
+            for rule in rules.iter_mut() {
+                let count = warnings.iter().filter(|w| w.id == rule.id()).count();
+                rule.warning_count_set(count);
+            }
+        }
+
+        self.last_run = Some(LintRun {
+            input: LintInput::new(domxml, validator_tags)?,
+            started,
+            duration: start.elapsed(),
+        });
+
+        Ok(())
+    }
+
+    /// Validate XML of a domain.
+    ///
+    /// Obtain XML of domain `dom` and validate it, see [`validate()`]. The `flags` are passed to
+    /// `virDomainGetXMLDesc()`, e.g. pass `VIR_DOMAIN_XML_INACTIVE` to validate the inactive XML
+    /// (i.e. the one used on next start) of a running domain, or 0 for the live XML.
+    ///
+    /// [`validate()`]: VirtLint::validate
+    pub fn validate_domain(
+        &mut self,
+        dom: &Domain,
+        flags: u32,
+        validator_tags: &[String],
+        error_on_no_connect: bool,
+    ) -> VirtLintResult<()> {
+        let domxml = dom.get_xml_desc(flags)?;
+
+        self.validate(&domxml, validator_tags, error_on_no_connect)
     }
 
     /// List all validator tags.
@@ -301,11 +854,82 @@ impl VirtLint {
     ///
     /// [`validate()`]: VirtLint::validate
     pub fn list_validator_tags() -> VirtLintResult<Vec<String>> {
-        let mut tags: Vec<String> = Validators::new().list_tags()?.into_iter().collect();
+        Self::list_validator_tags_with(&VirtLintConfig::default())
+    }
+
+    /// List all validator tags, looking for Lua and Python validators in directories given by
+    /// `config`.
+    ///
+    /// See [`list_validator_tags()`] and [`validator_paths()`].
+    ///
+    /// [`list_validator_tags()`]: VirtLint::list_validator_tags
+    /// [`validator_paths()`]: VirtLint::validator_paths
+    pub fn list_validator_tags_with(config: &VirtLintConfig) -> VirtLintResult<Vec<String>> {
+        let mut tags: Vec<String> = Validators::new(config).list_tags()?.into_iter().collect();
         tags.sort();
         Ok(tags)
     }
 
+    /// List all linting rules, sorted by rule ID.
+    ///
+    /// Unlike [`list_validator_tags()`], a record is returned for each rule (built-in, Lua or
+    /// Python one), describing the rule and what it needs to run. See [`validator_paths()`] for
+    /// where validators are looked for.
+    ///
+    /// [`list_validator_tags()`]: VirtLint::list_validator_tags
+    /// [`validator_paths()`]: VirtLint::validator_paths
+    pub fn list_validators(config: &VirtLintConfig) -> VirtLintResult<Vec<ValidatorInfo>> {
+        let mut validators = Validators::new(config).list()?;
+        validators.sort();
+        Ok(validators)
+    }
+
+    /// List directories searched for Lua and Python validators.
+    ///
+    /// These are given by `VIRT_LINT_LUA_PATH` and `VIRT_LINT_PYTHON_PATH` environment variables,
+    /// or by `config` (see [`VirtLintConfig::lua_paths_set()`]), or default to
+    /// `/usr/share/virt-lint/validators_{lua,python}` and their counterparts in the current
+    /// directory. Directories that don't exist are listed too. A validator in a later directory
+    /// overrides the one with the same rule ID in an earlier directory. A Lua and a Python
+    /// validator with the same rule ID are an error ([`VirtLintError::DuplicateRuleId`]).
+    pub fn validator_paths(config: &VirtLintConfig) -> Vec<PathBuf> {
+        Validators::paths(config)
+    }
+
+    /// Obtain documentation of all linting rules, sorted by rule ID.
+    ///
+    /// Built-in rules are documented in virt-lint sources, Lua and Python validators in their
+    /// header comment or docstring. See [`validator_paths()`] for where validators are looked
+    /// for.
+    ///
+    /// [`validator_paths()`]: VirtLint::validator_paths
+    pub fn rule_docs(config: &VirtLintConfig) -> VirtLintResult<Vec<RuleDoc>> {
+        let mut docs = Validators::new(config).rule_docs()?;
+        docs.sort();
+        Ok(docs)
+    }
+
+    /// Obtain documentation of linting rule `id`, or `None` if there is no such rule.
+    ///
+    /// See [`rule_docs()`].
+    ///
+    /// [`rule_docs()`]: VirtLint::rule_docs
+    pub fn rule_doc(config: &VirtLintConfig, id: &str) -> VirtLintResult<Option<RuleDoc>> {
+        Ok(Self::rule_docs(config)?.into_iter().find(|d| d.id() == id))
+    }
+
+    /// Apply fixes to domain XML.
+    ///
+    /// Apply chosen `fixes` (as obtained from [`VirtLintWarning::fix()`]) to domain XML `domxml`
+    /// and return the patched document. Fixes are applied in the order they are passed. An error
+    /// is returned if a fix does not apply, e.g. because its XPath matches no node.
+    ///
+    /// Please note that the document is reformatted by libxml, e.g. attributes are always
+    /// enclosed in double quotes.
+    pub fn apply_fixes(domxml: &str, fixes: &[&WarningFix]) -> VirtLintResult<String> {
+        fixes::apply_fixes(domxml, fixes)
+    }
+
     /// Obtain linting warnings.
     ///
     /// See [`validate()`].
@@ -316,4 +940,35 @@ impl VirtLint {
         warnings.sort();
         warnings
     }
+
+    /// Obtain rules executed during validation, with their outcome.
+    ///
+    /// Rules that needed information about the host which was not available (e.g. in the offline
+    /// mode) are marked as skipped, rules that returned an error as failed. Hence a run without
+    /// warnings, but with skipped rules, did not check everything. See [`validate()`].
+    ///
+    /// [`validate()`]: VirtLint::validate
+    pub fn rules(&self) -> Vec<RuleRun> {
+        self.rules.lock().expect("Mutex poisoned").clone()
+    }
+
+    /// Obtain report of the last validation run.
+    ///
+    /// Returns `None` if there was no (successful) validation yet. See [`validate()`].
+    ///
+    /// [`validate()`]: VirtLint::validate
+    pub fn report(&self) -> Option<LintReport> {
+        self.last_run.as_ref().map(|run| LintReport::new(self, run))
+    }
+
+    /// Obtain linting warnings waived in domain XML.
+    ///
+    /// See [`validate()`].
+    ///
+    /// [`validate()`]: VirtLint::validate
+    pub fn suppressed(&self) -> Vec<SuppressedWarning> {
+        let mut suppressed = self.suppressed.lock().expect("Mutex poisoned").clone();
+        suppressed.sort();
+        suppressed
+    }
 }
@@ -0,0 +1,240 @@
+/* SPDX-License-Identifier: LGPL-3.0-or-later */
+
+//! Linting of many domain XMLs at once.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use virt::connect::Connect;
+use virt_lint::*;
+
+/// A domain XML to lint.
+pub struct Input {
+    /// Where the domain XML came from (file or domain name), `None` for stdin
+    pub source: Option<String>,
+    /// The file the domain XML was read from
+    pub path: Option<PathBuf>,
+    pub domxml: String,
+}
+
+impl Input {
+    pub fn from_file(path: PathBuf, domxml: String) -> Self {
+        Self {
+            source: Some(path.display().to_string()),
+            path: Some(path),
+            domxml,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        self.source.as_deref().unwrap_or("<stdin>")
+    }
+}
+
+fn is_glob(s: &str) -> bool {
+    s.contains(['*', '?', '['])
+}
+
+/// Find `*.xml` files under directory `dir`, recursively.
+fn find_xml(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+    let mut paths: Vec<PathBuf> = Vec::new();
+
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("{}: {e}", dir.display()))?;
+        paths.push(entry.path());
+    }
+
+    paths.sort();
+
+    for path in paths {
+        if path.is_dir() {
+            find_xml(&path, files)?;
+        } else if path.extension().is_some_and(|e| e == "xml") {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Expand `paths`, which can be files, directories (searched recursively for `*.xml` files) or
+/// glob patterns, into list of files.
+pub fn expand_paths(paths: &[PathBuf]) -> Result<Vec<PathBuf>, String> {
+    let mut files: Vec<PathBuf> = Vec::new();
+
+    for path in paths {
+        let mut matches: Vec<PathBuf> = Vec::new();
+        let pattern = path.to_string_lossy();
+
+        if path.exists() || !is_glob(&pattern) {
+            matches.push(path.clone());
+        } else {
+            let entries =
+                glob::glob(&pattern).map_err(|e| format!("Invalid pattern '{pattern}': {e}"))?;
+
+            for entry in entries {
+                matches.push(entry.map_err(|e| e.to_string())?);
+            }
+
+            if matches.is_empty() {
+                return Err(format!("No files match '{pattern}'"));
+            }
+        }
+
+        for m in matches {
+            if m.is_dir() {
+                find_xml(&m, &mut files)?;
+            } else {
+                files.push(m);
+            }
+        }
+    }
+
+    // Lint each file just once, even if given multiple times
+    let mut seen = std::collections::HashSet::new();
+    files.retain(|f| seen.insert(f.clone()));
+
+    Ok(files)
+}
+
+/// Which domain XML to lint with --all-domains.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum DomainXml {
+    /// The live XML of running domains, the inactive XML otherwise
+    Live,
+
+    /// The inactive XML, i.e. the one used on next start
+    Inactive,
+
+    /// Both the live and the inactive XML of running domains
+    Both,
+}
+
+/// Obtain XMLs of domains defined on `conn`. Optionally, only `active` or `inactive` domains
+/// are considered, and only those with name matching one of `patterns` (if any).
+pub fn domain_inputs(
+    conn: &Connect,
+    active: bool,
+    inactive: bool,
+    patterns: &[String],
+    xml: DomainXml,
+) -> Result<Vec<Input>, String> {
+    let mut flags = 0;
+    if active {
+        flags |= virt::sys::VIR_CONNECT_LIST_DOMAINS_ACTIVE;
+    }
+    if inactive {
+        flags |= virt::sys::VIR_CONNECT_LIST_DOMAINS_INACTIVE;
+    }
+
+    let patterns = patterns
+        .iter()
+        .map(|p| glob::Pattern::new(p).map_err(|e| format!("Invalid pattern '{p}': {e}")))
+        .collect::<Result<Vec<glob::Pattern>, String>>()?;
+
+    let domains = conn
+        .list_all_domains(flags)
+        .map_err(|e| format!("Unable to list domains: {e}"))?;
+
+    let mut inputs: Vec<Input> = Vec::new();
+
+    for dom in domains {
+        let name = dom
+            .get_name()
+            .map_err(|e| format!("Unable to get domain name: {e}"))?;
+
+        if !patterns.is_empty() && !patterns.iter().any(|p| p.matches(&name)) {
+            continue;
+        }
+
+        let running = dom
+            .is_active()
+            .map_err(|e| format!("{name}: Unable to get domain state: {e}"))?;
+
+        // Inactive domains have just one XML
+        let mut xmls = vec![];
+        if xml != DomainXml::Inactive || !running {
+            xmls.push((0, name.clone()));
+        }
+        if xml != DomainXml::Live && running {
+            xmls.push((
+                virt::sys::VIR_DOMAIN_XML_INACTIVE,
+                format!("{name} (inactive XML)"),
+            ));
+        }
+
+        for (flags, source) in xmls {
+            let domxml = dom
+                .get_xml_desc(flags)
+                .map_err(|e| format!("{source}: Unable to get domain XML: {e}"))?;
+
+            inputs.push(Input {
+                source: Some(source),
+                path: None,
+                domxml,
+            });
+        }
+    }
+
+    inputs.sort_by(|a, b| a.source.cmp(&b.source));
+    Ok(inputs)
+}
+
+fn lint(
+    vl: &mut VirtLint,
+    input: &Input,
+    tags: &[String],
+    error_on_no_connect: bool,
+) -> Result<LintReport, String> {
+    vl.validate(&input.domxml, tags, error_on_no_connect)
+        .map_err(|e| format!("Validation failed: {e}"))?;
+
+    let mut report = vl
+        .report()
+        .ok_or_else(|| String::from("No validation report"))?;
+    report.source_set(input.source.clone());
+
+    Ok(report)
+}
+
+/// Lint `inputs` in `jobs` threads. Each thread uses its own fork of `vl`, so that
+/// capabilities are fetched only once. Results are in the same order as `inputs`.
+pub fn lint_all(
+    vl: &VirtLint,
+    inputs: &[Input],
+    tags: &[String],
+    error_on_no_connect: bool,
+    jobs: usize,
+) -> Vec<Result<LintReport, String>> {
+    let next = &AtomicUsize::new(0);
+    let results: &Vec<Mutex<Option<Result<LintReport, String>>>> =
+        &inputs.iter().map(|_| Mutex::new(None)).collect();
+
+    std::thread::scope(|s| {
+        for _ in 0..jobs.clamp(1, inputs.len().max(1)) {
+            let mut fork = vl.fork();
+
+            s.spawn(move || loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                if i >= inputs.len() {
+                    break;
+                }
+
+                let result = lint(&mut fork, &inputs[i], tags, error_on_no_connect);
+                *results[i].lock().expect("Mutex poisoned") = Some(result);
+            });
+        }
+    });
+
+    results
+        .iter()
+        .map(|r| {
+            r.lock()
+                .expect("Mutex poisoned")
+                .take()
+                .unwrap_or_else(|| Err(String::from("Not linted")))
+        })
+        .collect()
+}
@@ -341,10 +341,31 @@ virt_lint_worker(virConnectPtr conn,
         }
         printf("]\t");
 
-        printf("domain=%s\tlevel=%s\tmsg=%s\n",
+        printf("id=%s\tdomain=%s\tlevel=%s\tmsg=%s\t",
+               NULLSTR(w->id),
                NULLSTR(WarningDomainToStr(w->domain)),
                NULLSTR(WarningLevelToStr(w->level)),
                NULLSTR(w->msg));
+
+        printf("locations=[");
+        for (i = 0; i < w->nlocations; i++) {
+            struct CVirtLintWarningLocation *l = &w->locations[i];
+
+            if (i > 0) {
+                printf(", ");
+            }
+            printf("\"%s", NULLSTR(l->xpath));
+            if (l->line > 0) {
+                printf(":%u", l->line);
+                if (l->column > 0) {
+                    printf(":%u", l->column);
+                }
+            }
+            printf("\"");
+        }
+        printf("]\t");
+
+        printf("data=%s\n", NULLSTR(w->data));
     }
 
     ret = 0;
@@ -0,0 +1,132 @@
+/* SPDX-License-Identifier: LGPL-3.0-or-later */
+
+//! Rendering of rule documentation: plain text for `explain`, and the full rule catalogue as
+//! Markdown or a man page.
+
+use virt_lint::*;
+
+/// Which format to render the rule catalogue in.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum DocsFormat {
+    /// Markdown
+    Markdown,
+
+    /// Man page (roff)
+    Man,
+}
+
+/// Format documentation of a single rule for terminal.
+pub fn explain(doc: &RuleDoc) -> String {
+    let mut out = format!("{}\n", doc.id());
+
+    out += &format!("  {}\n", summary(doc));
+    out += &format!("\nTags: {}\n", doc.tags().join(", "));
+
+    if !doc.description().is_empty() {
+        out += &format!("\n{}\n", doc.description());
+    }
+
+    if let Some(rationale) = doc.rationale() {
+        out += &format!("\nRationale: {rationale}\n");
+    }
+
+    if !doc.references().is_empty() {
+        out += "\nReferences:\n";
+        for r in doc.references() {
+            out += &format!("  {r}\n");
+        }
+    }
+
+    out
+}
+
+fn summary(doc: &RuleDoc) -> &str {
+    if doc.summary().is_empty() {
+        "(undocumented)"
+    } else {
+        doc.summary()
+    }
+}
+
+fn to_markdown(docs: &[RuleDoc]) -> String {
+    let mut out = String::from("# virt-lint rules\n");
+
+    for doc in docs {
+        out += &format!("\n## `{}`\n\n{}\n", doc.id(), summary(doc));
+
+        let tags: Vec<String> = doc.tags().iter().map(|t| format!("`{t}`")).collect();
+        out += &format!("\n**Tags:** {}\n", tags.join(", "));
+
+        if !doc.description().is_empty() {
+            out += &format!("\n{}\n", doc.description());
+        }
+
+        if let Some(rationale) = doc.rationale() {
+            out += &format!("\n**Rationale:** {rationale}\n");
+        }
+
+        if !doc.references().is_empty() {
+            out += "\n**References:**\n\n";
+            for r in doc.references() {
+                out += &format!("- <{r}>\n");
+            }
+        }
+    }
+
+    out
+}
+
+/// Escape `s` for roff. Lines must not start with a control character either.
+fn roff(s: &str) -> String {
+    s.replace('\\', "\\e")
+        .replace('-', "\\-")
+        .lines()
+        .map(|l| {
+            if l.starts_with(['.', '\'']) {
+                format!("\\&{l}")
+            } else {
+                l.to_string()
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn to_man(docs: &[RuleDoc]) -> String {
+    let mut out = String::from(".TH VIRT-LINT-RULES 7\n");
+
+    out += ".SH NAME\nvirt-lint-rules \\- linting rules of virt-lint\n";
+    out += ".SH DESCRIPTION\n";
+    out += "Rules can be explained individually by \\fBvirt-lint explain\\fR \\fIRULE\\fR.\n";
+    out += ".SH RULES\n";
+
+    for doc in docs {
+        out += &format!(".SS {}\n{}\n", roff(doc.id()), roff(summary(doc)));
+        out += &format!(".PP\n\\fBTags:\\fR {}\n", roff(&doc.tags().join(", ")));
+
+        for paragraph in doc.description().split("\n\n").filter(|p| !p.is_empty()) {
+            out += &format!(".PP\n{}\n", roff(paragraph));
+        }
+
+        if let Some(rationale) = doc.rationale() {
+            out += &format!(".PP\n\\fBRationale:\\fR {}\n", roff(rationale));
+        }
+
+        if !doc.references().is_empty() {
+            out += ".PP\n\\fBReferences:\\fR\n";
+            for r in doc.references() {
+                out += &format!(".IP \\(bu 2\n{}\n", roff(r));
+            }
+        }
+    }
+
+    out
+}
+
+/// Render documentation of all rules in `format`.
+pub fn to_docs(docs: &[RuleDoc], format: DocsFormat) -> String {
+    match format {
+        DocsFormat::Markdown => to_markdown(docs),
+        DocsFormat::Man => to_man(docs),
+    }
+}
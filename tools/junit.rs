@@ -0,0 +1,148 @@
+/* SPDX-License-Identifier: LGPL-3.0-or-later */
+
+//! JUnit XML output, e.g. for CI test dashboards.
+//!
+//! Each report (i.e. a linted domain XML) becomes a testsuite and each executed rule a testcase.
+//! Rules that failed to run (e.g. a bug in a Lua or Python validator) are reported as errors.
+
+use virt_lint::*;
+
+fn escape(s: &str) -> String {
+    let mut ret = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        match c {
+            '&' => ret.push_str("&amp;"),
+            '<' => ret.push_str("&lt;"),
+            '>' => ret.push_str("&gt;"),
+            '"' => ret.push_str("&quot;"),
+            '\'' => ret.push_str("&apos;"),
+            _ => ret.push(c),
+        }
+    }
+
+    ret
+}
+
+fn suite_name(report: &LintReport) -> String {
+    report
+        .input()
+        .source()
+        .or(report.input().domain_name())
+        .unwrap_or("-")
+        .to_string()
+}
+
+fn warning_text(w: &VirtLintWarning) -> String {
+    let (_, domain, level, msg) = w.get();
+    let locations: Vec<String> = w.locations().iter().map(|l| l.to_string()).collect();
+
+    format!(
+        "{level}: {msg} (domain={domain}, locations={})",
+        locations.join(", ")
+    )
+}
+
+fn testsuite(report: &LintReport, fail_on: WarningLevel) -> (String, usize, usize, usize, usize) {
+    let name = suite_name(report);
+    let mut cases = String::new();
+    let mut failures = 0;
+    let mut errors = 0;
+    let mut skipped = 0;
+
+    for rule in report.rules() {
+        let warnings: Vec<&VirtLintWarning> = report
+            .warnings()
+            .iter()
+            .filter(|w| w.id() == rule.id())
+            .collect();
+
+        // Levels are ordered from the most severe one
+        let (failing, other): (Vec<&VirtLintWarning>, Vec<&VirtLintWarning>) =
+            warnings.into_iter().partition(|w| *w.get().2 <= fail_on);
+
+        cases += &format!(
+            "    <testcase name=\"{}\" classname=\"{}\">\n",
+            escape(rule.id()),
+            escape(&name)
+        );
+
+        if let Some(error) = rule.failed() {
+            errors += 1;
+
+            let text = match error.traceback() {
+                Some(traceback) => format!("{error}\n{traceback}"),
+                None => error.to_string(),
+            };
+            cases += &format!(
+                "      <error message=\"{}\" type=\"{}\">{}</error>\n",
+                escape(error.message()),
+                rule.engine(),
+                escape(&text)
+            );
+        } else if !failing.is_empty() {
+            failures += 1;
+
+            let text: Vec<String> = failing.iter().map(|w| warning_text(w)).collect();
+            cases += &format!(
+                "      <failure message=\"{}\" type=\"{}\">{}</failure>\n",
+                escape(failing[0].get().3),
+                failing[0].get().2,
+                escape(&text.join("\n"))
+            );
+        } else if let Some(reason) = rule.skipped() {
+            skipped += 1;
+            cases += &format!("      <skipped message=\"{}\"/>\n", escape(reason));
+        }
+
+        // Warnings emitted by a rule before it failed are kept too
+        let other = if rule.failed().is_some() {
+            [failing, other].concat()
+        } else {
+            other
+        };
+
+        if !other.is_empty() {
+            let text: Vec<String> = other.iter().map(|w| warning_text(w)).collect();
+            cases += &format!(
+                "      <system-out>{}</system-out>\n",
+                escape(&text.join("\n"))
+            );
+        }
+
+        cases += "    </testcase>\n";
+    }
+
+    let tests = report.rules().len();
+    let suite = format!(
+        "  <testsuite name=\"{}\" tests=\"{tests}\" failures=\"{failures}\" errors=\"{errors}\" skipped=\"{skipped}\" time=\"{:.3}\">\n{cases}  </testsuite>\n",
+        escape(&name),
+        report.duration().as_secs_f64(),
+    );
+
+    (suite, tests, failures, errors, skipped)
+}
+
+/// Format `reports` as JUnit XML. Rules with warnings at `fail_on` level or more severe are
+/// reported as failures.
+pub fn to_junit(reports: &[LintReport], fail_on: WarningLevel) -> String {
+    let mut suites = String::new();
+    let mut tests = 0;
+    let mut failures = 0;
+    let mut errors = 0;
+    let mut skipped = 0;
+
+    for report in reports {
+        let (suite, t, f, e, s) = testsuite(report, fail_on);
+
+        suites += &suite;
+        tests += t;
+        failures += f;
+        errors += e;
+        skipped += s;
+    }
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites name=\"virt-lint\" tests=\"{tests}\" failures=\"{failures}\" errors=\"{errors}\" skipped=\"{skipped}\">\n{suites}</testsuites>"
+    )
+}
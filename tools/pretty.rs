@@ -0,0 +1,218 @@
+/* SPDX-License-Identifier: LGPL-3.0-or-later */
+
+//! Human readable output in the style of rustc diagnostics, intended for terminals.
+
+use virt_lint::*;
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const RED: &str = "\x1b[1;31m";
+const YELLOW: &str = "\x1b[1;33m";
+const CYAN: &str = "\x1b[1;36m";
+const BLUE: &str = "\x1b[1;34m";
+const GREEN: &str = "\x1b[1;32m";
+
+struct Painter {
+    color: bool,
+}
+
+impl Painter {
+    fn paint(&self, style: &str, s: &str) -> String {
+        if self.color {
+            format!("{style}{s}{RESET}")
+        } else {
+            s.to_string()
+        }
+    }
+
+    fn level(&self, level: &WarningLevel) -> String {
+        match level {
+            WarningLevel::Error => self.paint(RED, "error"),
+            WarningLevel::Warning => self.paint(YELLOW, "warning"),
+            WarningLevel::Notice => self.paint(CYAN, "notice"),
+        }
+    }
+}
+
+/// Render a snippet of `domxml` around `location` with the offending element underlined.
+fn snippet(p: &Painter, lines: &[&str], location: &WarningLocation, gutter: usize) -> String {
+    let mut ret = String::new();
+    let pipe = p.paint(BLUE, "|");
+
+    let (line, column) = match (location.line(), location.column()) {
+        (Some(line), Some(column)) if (line as usize) <= lines.len() => (line, column),
+        _ => {
+            ret += &format!("{:gutter$} {} at: {}\n", "", p.paint(BLUE, "="), location);
+            return ret;
+        }
+    };
+
+    let text = lines[line as usize - 1];
+    let start = (column as usize - 1).min(text.len());
+
+    // Underline the start tag, or till the end of line if it spans more lines
+    let end = text[start..]
+        .find('>')
+        .map(|i| start + i + 1)
+        .unwrap_or(text.len());
+
+    // Keep tabs so that the underline is aligned
+    let indent: String = text[..start]
+        .chars()
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+    let underline = "^".repeat(text[start..end].chars().count().max(1));
+
+    ret += &format!("{:gutter$} {pipe}\n", "");
+    ret += &format!(
+        "{} {pipe} {text}\n",
+        p.paint(BLUE, &format!("{line:>gutter$}"))
+    );
+    ret += &format!(
+        "{:gutter$} {pipe} {indent}{} {}\n",
+        "",
+        p.paint(RED, &underline),
+        location.xpath()
+    );
+
+    ret
+}
+
+fn warning(p: &Painter, source: &str, lines: &[&str], w: &VirtLintWarning) -> String {
+    let (_, domain, level, msg) = w.get();
+    let mut ret = format!(
+        "{}{}: {}\n",
+        p.level(level),
+        p.paint(BOLD, &format!("[{}]", w.id())),
+        p.paint(BOLD, msg)
+    );
+
+    let gutter = w
+        .locations()
+        .iter()
+        .filter_map(|l| l.line())
+        .max()
+        .unwrap_or(0)
+        .to_string()
+        .len();
+    let arrow = p.paint(BLUE, "-->");
+
+    if w.locations().is_empty() {
+        ret += &format!("{:gutter$}{arrow} {source} ({domain})\n", "");
+    }
+
+    for location in w.locations() {
+        match location.line() {
+            Some(line) => {
+                let column = location.column().unwrap_or(1);
+                ret += &format!("{:gutter$}{arrow} {source}:{line}:{column}\n", "");
+            }
+            None => ret += &format!("{:gutter$}{arrow} {source}\n", ""),
+        }
+
+        ret += &snippet(p, lines, location, gutter);
+    }
+
+    if let Some(fix) = w.fix() {
+        ret += &format!(
+            "{:gutter$} {} {}: {}\n",
+            "",
+            p.paint(BLUE, "="),
+            p.paint(BOLD, "help"),
+            fix.description()
+        );
+    }
+
+    ret
+}
+
+fn plural(n: usize, what: &str) -> String {
+    if n == 1 {
+        format!("{n} {what}")
+    } else {
+        format!("{n} {what}s")
+    }
+}
+
+/// Format `report` of validating `domxml` for terminal. Use `color` to enable ANSI colors.
+pub fn to_pretty(report: &LintReport, domxml: &str, color: bool) -> String {
+    let p = Painter { color };
+    let source = report.input().source().unwrap_or("<stdin>");
+    let lines: Vec<&str> = domxml.lines().collect();
+    let mut out: Vec<String> = Vec::new();
+
+    for w in report.warnings() {
+        out.push(warning(&p, source, &lines, w));
+    }
+
+    for s in report.suppressed() {
+        let w = s.warning();
+        out.push(format!(
+            "{}{}: {} ({})\n",
+            p.paint(BOLD, "suppressed"),
+            p.paint(BOLD, &format!("[{}]", w.id())),
+            w.get().3,
+            s.reason().unwrap_or("no reason given")
+        ));
+    }
+
+    for e in report.baseline_stale() {
+        out.push(format!(
+            "{}{}: no longer occurs, remove it from the baseline\n",
+            p.paint(GREEN, "fixed"),
+            p.paint(BOLD, &format!("[{}]", e.rule())),
+        ));
+    }
+
+    // Rules that did not check anything, so that a clean result is not mistaken for coverage
+    for r in report.rules() {
+        let (what, why) = match r.outcome() {
+            RuleOutcome::Ran => continue,
+            RuleOutcome::Skipped(reason) => (p.paint(BOLD, "skipped"), reason.to_string()),
+            RuleOutcome::Failed(error) => (p.paint(RED, "failed"), error.to_string()),
+        };
+
+        let mut text = format!("{what}{}: {why}\n", p.paint(BOLD, &format!("[{}]", r.id())));
+
+        if let Some(traceback) = r.failed().and_then(|e| e.traceback()) {
+            for line in traceback.trim_end().lines() {
+                text += &format!("  {}\n", line.trim_start());
+            }
+        }
+
+        out.push(text);
+    }
+
+    out.push(summary(&p, source, report.warnings()));
+    out.join("\n")
+}
+
+fn summary(p: &Painter, what: &str, warnings: &[VirtLintWarning]) -> String {
+    let count = |level: WarningLevel| warnings.iter().filter(|w| *w.get().2 == level).count();
+
+    if warnings.is_empty() {
+        return p.paint(GREEN, &format!("{what}: no problems found"));
+    }
+
+    let counts = [
+        plural(count(WarningLevel::Error), "error"),
+        plural(count(WarningLevel::Warning), "warning"),
+        plural(count(WarningLevel::Notice), "notice"),
+    ];
+    p.paint(BOLD, &format!("{what}: {}", counts.join(", ")))
+}
+
+/// Format aggregate summary of `reports`.
+pub fn to_pretty_total(reports: &[LintReport], color: bool) -> String {
+    let p = Painter { color };
+    let warnings: Vec<VirtLintWarning> = reports
+        .iter()
+        .flat_map(|r| r.warnings().iter().cloned())
+        .collect();
+
+    summary(
+        &p,
+        &format!("Total ({})", plural(reports.len(), "file")),
+        &warnings,
+    )
+}
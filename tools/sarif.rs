@@ -0,0 +1,221 @@
+/* SPDX-License-Identifier: LGPL-3.0-or-later */
+
+//! SARIF 2.1.0 output, e.g. for code-scanning integrations.
+//!
+//! See https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html
+
+use serde_json::{json, Map, Value};
+use std::path::Path;
+use virt_lint::*;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+const INFORMATION_URI: &str = "https://gitlab.com/MichalPrivoznik/virt-lint";
+
+fn level(level: &WarningLevel) -> &'static str {
+    match level {
+        WarningLevel::Error => "error",
+        WarningLevel::Warning => "warning",
+        WarningLevel::Notice => "note",
+    }
+}
+
+/// Map rule onto a reportingDescriptor. The level is the default one of the rule, i.e. before
+/// applying configuration.
+fn rule(info: &ValidatorInfo, doc: Option<&RuleDoc>) -> Value {
+    let mut descriptor = json!({
+        "id": info.id(),
+        "shortDescription": { "text": info.description() },
+        "properties": { "tags": info.tags() },
+    });
+
+    if let Some(l) = info.level() {
+        descriptor["defaultConfiguration"] = json!({ "level": level(&l) });
+    }
+
+    if let Some(doc) = doc {
+        if !doc.description().is_empty() {
+            descriptor["fullDescription"] = json!({ "text": doc.description() });
+        }
+
+        if let Some(rationale) = doc.rationale() {
+            descriptor["help"] = json!({ "text": rationale });
+        }
+
+        if let Some(uri) = doc.references().first() {
+            descriptor["helpUri"] = json!(uri);
+        }
+    }
+
+    descriptor
+}
+
+/// Format `path` as an absolute `file://` URI.
+fn file_uri(path: &Path) -> String {
+    let path = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    let mut uri = String::from("file://");
+
+    for b in path.to_string_lossy().bytes() {
+        if b.is_ascii_alphanumeric() || b"/-._~".contains(&b) {
+            uri.push(b as char);
+        } else {
+            uri.push_str(&format!("%{b:02X}"));
+        }
+    }
+
+    uri
+}
+
+/// Map warning locations onto SARIF locations. Physical locations are reported only if the
+/// domain XML was read from file `path`, not e.g. for domains obtained from hypervisor.
+fn locations(path: Option<&Path>, w: &VirtLintWarning) -> Vec<Value> {
+    w.locations()
+        .iter()
+        .map(|l| {
+            let mut location = Map::new();
+
+            if let Some(path) = path {
+                let mut physical = json!({ "artifactLocation": { "uri": file_uri(path) } });
+
+                if let Some(line) = l.line() {
+                    let mut region = json!({ "startLine": line });
+                    if let Some(column) = l.column() {
+                        region["startColumn"] = json!(column);
+                    }
+                    physical["region"] = region;
+                }
+
+                location.insert(String::from("physicalLocation"), physical);
+            }
+
+            location.insert(
+                String::from("logicalLocations"),
+                json!([{ "fullyQualifiedName": l.xpath(), "kind": "element" }]),
+            );
+
+            Value::Object(location)
+        })
+        .collect()
+}
+
+/// Map rules that failed to run onto toolExecutionNotifications of the invocation.
+fn notifications(reports: &[LintReport]) -> Vec<Value> {
+    let mut ret = Vec::new();
+
+    for rule in reports.iter().flat_map(|r| r.rules()) {
+        let error = match rule.failed() {
+            Some(e) => e,
+            None => continue,
+        };
+
+        let mut notification = json!({
+            "level": "error",
+            "message": { "text": error.message() },
+            "associatedRule": { "id": rule.id() },
+        });
+
+        if let Some(path) = error.path() {
+            let mut physical = json!({ "artifactLocation": { "uri": file_uri(path) } });
+            if let Some(line) = error.line() {
+                physical["region"] = json!({ "startLine": line });
+            }
+            notification["locations"] = json!([{ "physicalLocation": physical }]);
+        }
+
+        if let Some(traceback) = error.traceback() {
+            notification["properties"] = json!({ "traceback": traceback });
+        }
+
+        ret.push(notification);
+    }
+
+    ret
+}
+
+/// Format `reports` as a SARIF log with a single run. The `paths` are files the domain XMLs of
+/// `reports` were read from, if any. Rules are described by `validators` and `rule_docs`.
+pub fn to_sarif(
+    reports: &[LintReport],
+    paths: &[Option<&Path>],
+    validators: &[ValidatorInfo],
+    rule_docs: &[RuleDoc],
+) -> Value {
+    let mut rules: Vec<Value> = validators
+        .iter()
+        .map(|v| rule(v, rule_docs.iter().find(|d| d.id() == v.id())))
+        .collect();
+    let mut rule_ids: Vec<String> = validators.iter().map(|v| v.id().to_string()).collect();
+    let mut results: Vec<Value> = Vec::new();
+
+    let mut add = |path: Option<&Path>, w: &VirtLintWarning, reason: Option<Option<&str>>| {
+        let index = match rule_ids.iter().position(|id| id == w.id()) {
+            Some(i) => i,
+            None => {
+                rule_ids.push(w.id().to_string());
+                rules.push(json!({ "id": w.id() }));
+                rule_ids.len() - 1
+            }
+        };
+
+        let mut result = json!({
+            "ruleId": w.id(),
+            "ruleIndex": index,
+            "level": level(w.get().2),
+            "message": { "text": w.get().3 },
+            "locations": locations(path, w),
+        });
+
+        if !w.data().is_empty() {
+            result["properties"] = json!(w.data());
+        }
+
+        // Warnings waived in domain XML are reported as suppressed in source
+        if let Some(reason) = reason {
+            let mut suppression = json!({ "kind": "inSource" });
+            if let Some(reason) = reason {
+                suppression["justification"] = json!(reason);
+            }
+            result["suppressions"] = json!([suppression]);
+        }
+
+        results.push(result);
+    };
+
+    for (i, report) in reports.iter().enumerate() {
+        let path = paths.get(i).copied().flatten();
+
+        for w in report.warnings() {
+            add(path, w, None);
+        }
+
+        for s in report.suppressed() {
+            add(path, s.warning(), Some(s.reason()));
+        }
+    }
+
+    let notifications = notifications(reports);
+    let version = reports
+        .first()
+        .map(|r| r.version().to_string())
+        .unwrap_or_default();
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": SARIF_VERSION,
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "virt-lint",
+                    "version": version,
+                    "informationUri": INFORMATION_URI,
+                    "rules": rules,
+                }
+            },
+            "invocations": [{
+                "executionSuccessful": notifications.is_empty(),
+                "toolExecutionNotifications": notifications,
+            }],
+            "results": results,
+        }]
+    })
+}
@@ -1,90 +1,816 @@
 /* SPDX-License-Identifier: LGPL-3.0-or-later */
 
-use clap::Parser;
+mod batch;
+mod docs;
+mod junit;
+mod pretty;
+mod sarif;
+mod watch;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use similar::TextDiff;
 use std::fs;
 use std::io;
-use std::io::Read;
+use std::io::{BufRead, IsTerminal, Read, Write};
+use std::process::ExitCode;
+use std::time::Duration;
 
 use virt::connect::Connect;
+use virt::domain::Domain;
 use virt_lint::*;
 
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum FixMode {
+    /// Apply all suggested fixes
+    Apply,
+
+    /// Show each suggested fix as a diff and ask before applying it
+    Prompt,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum FailOn {
+    /// Fail on errors only
+    Error,
+
+    /// Fail on errors and warnings
+    Warning,
+
+    /// Fail on any finding
+    Notice,
+}
+
+impl From<FailOn> for WarningLevel {
+    fn from(f: FailOn) -> Self {
+        match f {
+            FailOn::Error => WarningLevel::Error,
+            FailOn::Warning => WarningLevel::Warning,
+            FailOn::Notice => WarningLevel::Notice,
+        }
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum OutputFormat {
+    /// Human readable text with domain XML snippets, or plain text if not printing to terminal
+    Pretty,
+
+    /// Human readable text, a line per warning
+    Text,
+
+    /// JSON report
+    Json,
+
+    /// JSON report on a single line (newline-delimited JSON)
+    Ndjson,
+
+    /// SARIF 2.1.0 log, e.g. for code-scanning integrations
+    Sarif,
+
+    /// JUnit XML, a testcase per rule. Rules with warnings at the --fail-on level fail
+    Junit,
+}
+
+/// No findings at or above the --fail-on level and within the --max-warnings budget
+const EXIT_SUCCESS: u8 = 0;
+
+/// Findings at or above the --fail-on level or more than --max-warnings of them
+const EXIT_FINDINGS: u8 = 1;
+
+/// Internal error, e.g. unable to read the domain XML or to connect to hypervisor, or a rule
+/// failed to run
+const EXIT_ERROR: u8 = 2;
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Explain what a rule checks and why it matters
+    Explain {
+        /// Rule ID, or a tag to explain all rules with the tag
+        rule: String,
+    },
+
+    /// Print documentation of all rules
+    Docs {
+        /// Documentation format
+        #[arg(long, value_enum, default_value_t = docs::DocsFormat::Markdown)]
+        format: docs::DocsFormat,
+    },
+}
+
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// connection uri
     #[arg(short = 'c', long = "connect", value_name = "URI")]
     uri: Option<String>,
 
-    /// The path to the domain XML, otherwise read the XML from stdin
-    #[arg(short, long, value_name = "FILE")]
-    path: Option<std::path::PathBuf>,
+    /// Do not connect to hypervisor, use --caps and --domcaps instead
+    #[arg(long, conflicts_with_all = ["uri", "all_domains", "redefine"])]
+    offline: bool,
+
+    /// The path to capabilities XML (virsh capabilities)
+    #[arg(long, value_name = "FILE")]
+    caps: Option<std::path::PathBuf>,
+
+    /// The path to domain capabilities XML (virsh domcapabilities). Can be given multiple times
+    #[arg(long, value_name = "FILE")]
+    domcaps: Vec<std::path::PathBuf>,
+
+    /// Fail if a rule needs a connection that is not available, instead of skipping the rule
+    #[arg(long)]
+    error_on_no_connect: bool,
+
+    /// The path to the domain XML, a directory with domain XMLs (*.xml) or a glob pattern.
+    /// Can be given multiple times. Otherwise read the XML from stdin
+    #[arg(short, long, value_name = "PATH", num_args = 1..)]
+    path: Vec<std::path::PathBuf>,
+
+    /// Lint domain XMLs (like --path) and then again whenever they or validators change
+    #[arg(
+        long,
+        value_name = "PATH",
+        num_args = 1..,
+        conflicts_with_all = ["path", "format", "fix", "write_baseline"]
+    )]
+    watch: Vec<std::path::PathBuf>,
+
+    /// Lint all domains defined on the connection, instead of domain XML files
+    #[arg(long, conflicts_with_all = ["path", "watch"])]
+    all_domains: bool,
+
+    /// With --all-domains, lint only active domains
+    #[arg(long, requires = "all_domains", conflicts_with = "inactive")]
+    active: bool,
+
+    /// With --all-domains, lint only inactive domains
+    #[arg(long, requires = "all_domains")]
+    inactive: bool,
+
+    /// With --all-domains, lint only domains with name matching the glob pattern. Can be given
+    /// multiple times
+    #[arg(long = "name", value_name = "PATTERN", requires = "all_domains")]
+    names: Vec<String>,
+
+    /// With --all-domains, which domain XML to lint
+    #[arg(long, value_name = "XML", default_value = "live")]
+    domain_xml: batch::DomainXml,
+
+    /// Number of domain XMLs to lint in parallel
+    #[arg(short, long, value_name = "N", default_value_t = 1)]
+    jobs: usize,
 
     /// Turn debugging information on
     #[arg(short, long)]
     debug: bool,
 
-    /// Comma separated list of validator tags, empty means all
+    /// Comma separated list of validator tags or tag expressions (e.g. 'common and not numa'),
+    /// empty means all
     #[arg(short, long, value_delimiter = ',')]
     validators: Option<Vec<String>>,
 
+    /// Don't run rules with this tag (or matching this tag expression). Can be given multiple
+    /// times
+    #[arg(long, value_name = "TAG")]
+    skip: Vec<String>,
+
     /// List known validator tags
     #[arg(short = 'l', long = "list-validator-tags")]
     list: bool,
+
+    /// Apply suggested fixes and print fixed domain XML
+    #[arg(long, value_name = "MODE", num_args = 0..=1, require_equals = true, default_missing_value = "apply")]
+    fix: Option<FixMode>,
+
+    /// Write fixed domain XML back to the file given by --path instead of stdout
+    #[arg(short, long, requires = "fix", requires = "path")]
+    in_place: bool,
+
+    /// Redefine the domain with fixed domain XML
+    #[arg(long, requires = "fix")]
+    redefine: bool,
+
+    /// Report only warnings not recorded in the baseline file
+    #[arg(long, value_name = "FILE")]
+    baseline: Option<std::path::PathBuf>,
+
+    /// Record current warnings into the baseline file
+    #[arg(long, value_name = "FILE", conflicts_with = "baseline")]
+    write_baseline: Option<std::path::PathBuf>,
+
+    /// Output format [default: pretty]
+    #[arg(long, value_name = "FORMAT")]
+    format: Option<OutputFormat>,
+
+    /// Load configuration from FILE too, on top of the system, user and project ones
+    #[arg(long, value_name = "FILE")]
+    config: Option<std::path::PathBuf>,
+
+    /// Don't load system, user and project configuration files
+    #[arg(long)]
+    no_config: bool,
+
+    /// Exit with failure if there is a warning of this level or more severe
+    #[arg(long, value_name = "LEVEL", default_value = "error")]
+    fail_on: FailOn,
+
+    /// Exit with failure if there are more than N warnings (of any level)
+    #[arg(long, value_name = "N")]
+    max_warnings: Option<usize>,
+
+    /// Go on with other rules when a rule fails (e.g. a buggy Lua or Python validator) and
+    /// report the failure along with warnings
+    #[arg(long)]
+    keep_going: bool,
+
+    /// Print how long each rule took, the slowest first, on stderr
+    #[arg(long, conflicts_with = "watch")]
+    timings: bool,
 }
 
-fn main() {
-    let mut domxml = String::new();
+/// Ask user on the terminal whether to apply a fix. Stdin can't be used as it might have been
+/// used to read the domain XML.
+fn prompt(question: &str) -> Result<bool, String> {
+    let tty = fs::File::open("/dev/tty").map_err(|e| format!("Unable to open terminal: {e}"))?;
+    let mut reader = io::BufReader::new(tty);
 
-    let cli = Args::parse();
+    loop {
+        eprint!("{question} [y/n] ");
+        io::stderr()
+            .flush()
+            .map_err(|e| format!("Unable to flush stderr: {e}"))?;
+
+        let mut answer = String::new();
+        let len = reader
+            .read_line(&mut answer)
+            .map_err(|e| format!("Unable to read answer: {e}"))?;
+        if len == 0 {
+            return Ok(false);
+        }
+
+        match answer.trim() {
+            "y" | "Y" | "yes" => return Ok(true),
+            "n" | "N" | "no" => return Ok(false),
+            _ => continue,
+        }
+    }
+}
+
+/// Apply fixes suggested by warnings to `domxml` and return the fixed domain XML.
+fn fix(domxml: &str, warnings: &[VirtLintWarning], mode: FixMode) -> Result<String, String> {
+    // Let libxml reformat the original so that diffs show only changes made by fixes.
+    let mut fixed = VirtLint::apply_fixes(domxml, &[])
+        .map_err(|e| format!("Unable to parse domain XML: {e}"))?;
+
+    for w in warnings.iter() {
+        let f = match w.fix() {
+            Some(f) => f,
+            None => continue,
+        };
+
+        let new = match VirtLint::apply_fixes(&fixed, &[f]) {
+            Ok(x) => x,
+            Err(e) => {
+                eprintln!("Unable to apply fix '{}': {e}", f.description());
+                continue;
+            }
+        };
+
+        if mode == FixMode::Prompt {
+            let diff = TextDiff::from_lines(&fixed, &new);
+
+            eprintln!("{}: {}", w.id(), f.description());
+            eprint!(
+                "{}",
+                diff.unified_diff()
+                    .context_radius(3)
+                    .header("original", "fixed")
+            );
+
+            if !prompt("Apply this fix?")? {
+                continue;
+            }
+        }
+
+        fixed = new;
+    }
+
+    Ok(fixed)
+}
+
+/// Redefine domain, which must be defined already, with `domxml`.
+fn redefine(conn: &Connect, domxml: &str) -> Result<(), String> {
+    let doc = libxml::parser::Parser::default()
+        .parse_string(domxml)
+        .map_err(|e| format!("Unable to parse domain XML: {e}"))?;
+    let name = libxml::xpath::Context::new(&doc)
+        .and_then(|mut ctxt| ctxt.findvalues("/domain/name", None))
+        .unwrap_or_default()
+        .concat();
+
+    if let Err(e) = Domain::lookup_by_name(conn, &name) {
+        return Err(format!("Domain '{name}' is not defined: {e}"));
+    }
+
+    if let Err(e) = Domain::define_xml(conn, domxml) {
+        return Err(format!("Unable to redefine domain '{name}': {e}"));
+    }
+
+    Ok(())
+}
+
+/// Format report as text, a line per warning.
+fn format_text(report: &LintReport) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    for w in report.warnings().iter() {
+        let (tags, domain, level, msg) = w.get();
+        let locations: Vec<String> = w.locations().iter().map(|l| l.to_string()).collect();
+        let data: Vec<String> = w.data().iter().map(|(k, v)| format!("{k}={v}")).collect();
+        lines.push(format!(
+            "Warning: tags={:?}\tid={}\tdomain={domain}\tlevel={level}\tmsg={msg}\tlocations={:?}\tdata={:?}",
+            tags,
+            w.id(),
+            locations,
+            data
+        ));
+    }
+
+    for s in report.suppressed().iter() {
+        let w = s.warning();
+        lines.push(format!(
+            "Suppressed: id={}\tmsg={}\treason={}",
+            w.id(),
+            w.get().3,
+            s.reason().unwrap_or("-")
+        ));
+    }
+
+    for e in report.baseline_stale().iter() {
+        lines.push(format!(
+            "Fixed: id={}\tlocations={:?}",
+            e.rule(),
+            e.locations()
+        ));
+    }
+
+    for r in report.rules().iter() {
+        match r.outcome() {
+            RuleOutcome::Ran => {}
+            RuleOutcome::Skipped(reason) => {
+                lines.push(format!("Skipped: id={}\treason={reason}", r.id()))
+            }
+            RuleOutcome::Failed(error) => {
+                lines.push(format!("Failed: id={}\terror={error}", r.id()))
+            }
+        }
+    }
+
+    lines
+}
+
+/// Format per-file summary as text.
+fn format_text_summary(what: &str, warnings: &[VirtLintWarning]) -> String {
+    let count = |level: WarningLevel| warnings.iter().filter(|w| *w.get().2 == level).count();
+
+    format!(
+        "{what}\terrors={}\twarnings={}\tnotices={}",
+        count(WarningLevel::Error),
+        count(WarningLevel::Warning),
+        count(WarningLevel::Notice)
+    )
+}
+
+/// Format `reports` in given `format`. The `inputs` are those of `reports`.
+fn format_reports(
+    cli: &Args,
+    config: &VirtLintConfig,
+    inputs: &[&batch::Input],
+    reports: &[LintReport],
+    terminal: bool,
+) -> Result<String, String> {
+    let json_err = |e| format!("Unable to format report: {e}");
+    let batch = inputs.len() > 1;
+    let color = std::env::var_os("NO_COLOR").is_none();
+
+    let output = match cli.format.unwrap_or(OutputFormat::Pretty) {
+        OutputFormat::Pretty if terminal => {
+            let mut out: Vec<String> = inputs
+                .iter()
+                .zip(reports)
+                .map(|(i, r)| pretty::to_pretty(r, &i.domxml, color))
+                .collect();
+
+            if batch {
+                out.push(pretty::to_pretty_total(reports, color));
+            }
+
+            out.join("\n\n")
+        }
+        OutputFormat::Pretty | OutputFormat::Text => {
+            let mut out: Vec<String> = Vec::new();
+
+            for (i, r) in inputs.iter().zip(reports) {
+                out.extend(format_text(r));
+
+                if batch {
+                    let what = format!("Summary: file={}", i.name());
+                    out.push(format_text_summary(&what, r.warnings()));
+                }
+            }
+
+            if batch {
+                let warnings: Vec<VirtLintWarning> = reports
+                    .iter()
+                    .flat_map(|r| r.warnings().iter().cloned())
+                    .collect();
+                let what = format!("Total: files={}", reports.len());
+                out.push(format_text_summary(&what, &warnings));
+            }
+
+            out.join("\n")
+        }
+        OutputFormat::Json if batch => {
+            serde_json::to_string_pretty(reports).map_err(|e| json_err(e.to_string()))?
+        }
+        OutputFormat::Json => match reports.first() {
+            Some(r) => r.to_json().map_err(|e| json_err(e.to_string()))?,
+            None => String::new(),
+        },
+        OutputFormat::Ndjson => {
+            let mut out: Vec<String> = Vec::new();
+            for r in reports {
+                out.push(r.to_json_line().map_err(|e| json_err(e.to_string()))?);
+            }
+            out.join("\n")
+        }
+        OutputFormat::Sarif => {
+            let rules_err = |e: errors::VirtLintError| format!("Unable to obtain rules: {e}");
+            let validators = VirtLint::list_validators(config).map_err(rules_err)?;
+            let rule_docs = VirtLint::rule_docs(config).map_err(rules_err)?;
+            let paths: Vec<Option<&std::path::Path>> =
+                inputs.iter().map(|i| i.path.as_deref()).collect();
+
+            let sarif = sarif::to_sarif(reports, &paths, &validators, &rule_docs);
+            serde_json::to_string_pretty(&sarif).map_err(|e| json_err(e.to_string()))?
+        }
+        OutputFormat::Junit => junit::to_junit(reports, cli.fail_on.into()),
+    };
+
+    Ok(output)
+}
+
+/// Format execution times of rules in `reports`, the slowest rule first. Times and warnings of
+/// a rule are summed up over all reports.
+fn format_timings(reports: &[LintReport]) -> String {
+    let mut rules: Vec<(&str, ValidatorEngine, Duration, usize)> = Vec::new();
+
+    for r in reports.iter().flat_map(|r| r.rules()) {
+        match rules.iter_mut().find(|(id, ..)| *id == r.id()) {
+            Some((_, _, duration, warnings)) => {
+                *duration += r.duration();
+                *warnings += r.warning_count();
+            }
+            None => rules.push((r.id(), r.engine(), r.duration(), r.warning_count())),
+        }
+    }
+
+    rules.sort_by(|a, b| b.2.cmp(&a.2).then(a.0.cmp(b.0)));
+
+    let ms = |d: Duration| format!("{:.3}ms", d.as_secs_f64() * 1000.0);
+    let mut lines = vec![format!(
+        "{:>12}  {:<8}  {:>8}  rule",
+        "time", "engine", "warnings"
+    )];
+
+    for (id, engine, duration, warnings) in &rules {
+        lines.push(format!(
+            "{:>12}  {:<8}  {warnings:>8}  {id}",
+            ms(*duration),
+            engine.to_string().to_lowercase()
+        ));
+    }
+
+    let total: Duration = rules.iter().map(|r| r.2).sum();
+    lines.push(format!("{:>12}  total", ms(total)));
+
+    lines.join("\n")
+}
+
+/// Combine validator tag expressions `tags` (a rule matching any of them is run) with `skip`
+/// ones into a single expression.
+fn tag_selection(tags: Vec<String>, skip: &[String]) -> Vec<String> {
+    if skip.is_empty() {
+        return tags;
+    }
+
+    let mut expr: Vec<String> = Vec::new();
+
+    if !tags.is_empty() {
+        expr.push(format!("({})", tags.join(") or (")));
+    }
+
+    expr.extend(skip.iter().map(|s| format!("not ({s})")));
+
+    vec![expr.join(" and ")]
+}
+
+/// Decide the exit code from reported warnings.
+fn exit_code(warnings: &[VirtLintWarning], fail_on: FailOn, max_warnings: Option<usize>) -> u8 {
+    let fail_on = WarningLevel::from(fail_on);
+
+    // Levels are ordered from the most severe one
+    if warnings.iter().any(|w| *w.get().2 <= fail_on) {
+        return EXIT_FINDINGS;
+    }
+
+    match max_warnings {
+        Some(max) if warnings.len() > max => EXIT_FINDINGS,
+        _ => EXIT_SUCCESS,
+    }
+}
+
+fn explain(config: &VirtLintConfig, rule: &str) -> Result<u8, String> {
+    let rule_docs =
+        VirtLint::rule_docs(config).map_err(|e| format!("Unable to obtain rules: {e}"))?;
+
+    // Rule IDs take precedence over tags
+    let matching: Vec<&RuleDoc> = match rule_docs.iter().find(|d| d.id() == rule) {
+        Some(doc) => vec![doc],
+        None => rule_docs
+            .iter()
+            .filter(|d| d.tags().iter().any(|t| t == rule))
+            .collect(),
+    };
+
+    if matching.is_empty() {
+        return Err(format!("Unknown rule: {rule}"));
+    }
+
+    let text: Vec<String> = matching.into_iter().map(docs::explain).collect();
+    print!("{}", text.join("\n"));
+
+    Ok(EXIT_SUCCESS)
+}
+
+/// Load configuration files. Command line options take precedence over them.
+fn load_config(cli: &Args) -> Result<VirtLintConfig, String> {
+    let mut config = if cli.no_config {
+        VirtLintConfig::new()
+    } else {
+        VirtLintConfig::load_default().map_err(|e| format!("Unable to load configuration: {e}"))?
+    };
+
+    if let Some(file) = &cli.config {
+        let extra =
+            VirtLintConfig::load(file).map_err(|e| format!("Unable to load configuration: {e}"))?;
+        config.merge(extra);
+    }
+
+    Ok(config)
+}
+
+fn run(mut cli: Args) -> Result<u8, String> {
+    let mut ret = EXIT_SUCCESS;
+    let mut config = load_config(&cli)?;
+
+    if cli.keep_going {
+        config.keep_going_set(true);
+    }
+
+    if !cli.offline && cli.uri.is_none() {
+        cli.uri = config.uri().map(String::from);
+    }
+
+    if cli.validators.is_none() && !config.validator_tags().is_empty() {
+        cli.validators = Some(config.validator_tags().to_vec());
+    }
+
+    if let (None, Some(format)) = (cli.format, config.format()) {
+        let format = OutputFormat::from_str(format, true)
+            .map_err(|e| format!("Invalid output format in configuration: {e}"))?;
+        cli.format = Some(format);
+    }
+
+    match &cli.command {
+        Some(Command::Explain { rule }) => return explain(&config, rule),
+        Some(Command::Docs { format }) => {
+            let rule_docs =
+                VirtLint::rule_docs(&config).map_err(|e| format!("Unable to obtain rules: {e}"))?;
+            print!("{}", docs::to_docs(&rule_docs, *format));
+            return Ok(EXIT_SUCCESS);
+        }
+        None => {}
+    }
 
     if cli.list {
         if cli.debug {
             println!("Validator tags:");
         }
 
-        VirtLint::list_validator_tags()
-            .expect("Unable to list validator tags")
+        VirtLint::list_validator_tags_with(&config)
+            .map_err(|e| format!("Unable to list validator tags: {e}"))?
             .iter()
             .for_each(|tag| println!("{tag}"));
 
-        return;
+        return Ok(EXIT_SUCCESS);
     }
 
-    if let Some(file) = cli.path {
-        domxml = fs::read_to_string(file).expect("Unable to read the file");
-    } else {
+    // Redefining domains with their live XML would make the live state persistent
+    if cli.all_domains && cli.redefine && cli.domain_xml != batch::DomainXml::Inactive {
+        return Err(String::from(
+            "--redefine with --all-domains requires --domain-xml=inactive",
+        ));
+    }
+
+    let mut conn = None;
+
+    if !cli.offline {
+        if cli.debug {
+            dbg!(
+                "Attempting to connect to hypervisor: '{:?}'",
+                cli.uri.as_deref()
+            );
+        }
+
+        match Connect::open(cli.uri.as_deref()) {
+            Ok(c) => conn = Some(c),
+            Err(e) => return Err(format!("No connection to hypervisor: {e}")),
+        }
+    }
+
+    let mut inputs: Vec<batch::Input> = Vec::new();
+
+    if let (true, Some(c)) = (cli.all_domains, &conn) {
+        inputs = batch::domain_inputs(c, cli.active, cli.inactive, &cli.names, cli.domain_xml)?;
+    } else if cli.path.is_empty() && cli.watch.is_empty() {
+        let mut domxml = String::new();
         io::stdin()
             .read_to_string(&mut domxml)
-            .expect("Unable to read stdin");
+            .map_err(|e| format!("Unable to read stdin: {e}"))?;
+        inputs.push(batch::Input {
+            source: None,
+            path: None,
+            domxml,
+        });
+    } else {
+        for path in batch::expand_paths(&cli.path)? {
+            match fs::read_to_string(&path) {
+                Ok(domxml) => inputs.push(batch::Input::from_file(path, domxml)),
+                Err(e) => {
+                    eprintln!("{}: Unable to read the file: {e}", path.display());
+                    ret = EXIT_ERROR;
+                }
+            }
+        }
     }
 
-    if cli.debug {
-        dbg!(
-            "Attempting to connect to hypervisor: '{:?}'",
-            cli.uri.as_deref()
-        );
+    // Fixed domain XMLs can't be printed all to stdout
+    if cli.fix.is_some() && inputs.len() > 1 && !cli.in_place && !cli.redefine {
+        return Err(String::from(
+            "--fix with multiple inputs requires --in-place or --redefine",
+        ));
     }
 
-    let mut conn = match Connect::open(cli.uri.as_deref()) {
-        Ok(c) => c,
-        Err(e) => panic!("No connection to hypervisor: {}", e),
+    let mut l = VirtLint::new(conn.as_ref());
+    l.config_set(config);
+
+    if let Some(file) = &cli.caps {
+        let capsxml = fs::read_to_string(file)
+            .map_err(|e| format!("Unable to read capabilities: {}: {e}", file.display()))?;
+        l.capabilities_set(Some(capsxml))
+            .map_err(|e| format!("Unable to set capabilities: {e}"))?;
+    }
+
+    for file in &cli.domcaps {
+        let domcapsxml = fs::read_to_string(file).map_err(|e| {
+            format!(
+                "Unable to read domain capabilities: {}: {e}",
+                file.display()
+            )
+        })?;
+        l.domain_capabilities_add(domcapsxml)
+            .map_err(|e| format!("Unable to add domain capabilities: {e}"))?;
+    }
+
+    if let Some(file) = &cli.baseline {
+        let baseline =
+            Baseline::load(file).map_err(|e| format!("Unable to load the baseline: {e}"))?;
+        l.baseline_set(Some(baseline));
+    }
+
+    let tags = tag_selection(cli.validators.clone().unwrap_or_default(), &cli.skip);
+
+    if !cli.watch.is_empty() {
+        return watch::watch(&l, &cli.watch, &tags, cli.error_on_no_connect, cli.jobs);
+    }
+    let results = batch::lint_all(&l, &inputs, &tags, cli.error_on_no_connect, cli.jobs);
+
+    let mut linted: Vec<&batch::Input> = Vec::new();
+    let mut reports: Vec<LintReport> = Vec::new();
+
+    for (input, result) in inputs.iter().zip(results) {
+        match result {
+            Ok(report) => {
+                linted.push(input);
+                reports.push(report);
+            }
+            Err(e) => {
+                eprintln!("{}: {e}", input.name());
+                ret = EXIT_ERROR;
+            }
+        }
+    }
+
+    // The lint is incomplete, even though the failures were reported along with warnings
+    if reports
+        .iter()
+        .any(|r| r.rules().iter().any(|x| x.failed().is_some()))
+    {
+        ret = EXIT_ERROR;
+    }
+
+    // With --fix the stdout is reserved for fixed domain XML
+    let terminal = if cli.fix.is_some() {
+        io::stderr().is_terminal()
+    } else {
+        io::stdout().is_terminal()
     };
 
-    let mut l = VirtLint::new(Some(&conn));
+    let output = format_reports(&cli, l.config(), &linted, &reports, terminal)?;
 
-    if let Err(e) = conn.close() {
-        panic!("Failed to disconnect from hypervisor: {}", e);
+    if !output.is_empty() {
+        if cli.fix.is_some() {
+            eprintln!("{output}");
+        } else {
+            println!("{output}");
+        }
     }
 
-    if let Err(e) = l.validate(&domxml, &cli.validators.unwrap_or_default(), false) {
-        println!("Validation failed: {}", e);
+    if cli.timings {
+        eprintln!("{}", format_timings(&reports));
     }
 
-    for w in l.warnings().iter() {
-        let (tags, domain, level, msg) = w.get();
-        println!(
-            "Warning: tags={:?}\tdomain={domain}\tlevel={level}\tmsg={msg}",
-            tags
-        );
+    if let Some(file) = &cli.write_baseline {
+        let mut baseline = Baseline::new();
+        for (input, report) in linted.iter().zip(&reports) {
+            baseline
+                .add(&input.domxml, report.warnings())
+                .map_err(|e| format!("Unable to record warnings: {e}"))?;
+        }
+        baseline
+            .save(file)
+            .map_err(|e| format!("Unable to save the baseline: {e}"))?;
+    }
+
+    if let Some(mode) = cli.fix {
+        for (input, report) in linted.iter().zip(&reports) {
+            let fixed = fix(&input.domxml, report.warnings(), mode)?;
+
+            if let (true, Some(c)) = (cli.redefine, &conn) {
+                redefine(c, &fixed)?;
+            }
+
+            match &input.path {
+                Some(file) if cli.in_place => {
+                    fs::write(file, fixed).map_err(|e| format!("Unable to write the file: {e}"))?;
+                }
+                _ if linted.len() > 1 => {}
+                _ => print!("{fixed}"),
+            }
+        }
+    }
+
+    if ret == EXIT_SUCCESS {
+        let warnings: Vec<VirtLintWarning> = reports
+            .iter()
+            .flat_map(|r| r.warnings().iter().cloned())
+            .collect();
+        ret = exit_code(&warnings, cli.fail_on, cli.max_warnings);
+    }
+
+    // The VirtLint object holds its own reference to the connection
+    drop(l);
+
+    if let Some(mut c) = conn {
+        if let Err(e) = c.close() {
+            return Err(format!("Failed to disconnect from hypervisor: {e}"));
+        }
+    }
+
+    Ok(ret)
+}
+
+fn main() -> ExitCode {
+    let cli = Args::parse();
+
+    match run(cli) {
+        Ok(ret) => ExitCode::from(ret),
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::from(EXIT_ERROR)
+        }
     }
 }
@@ -0,0 +1,223 @@
+/* SPDX-License-Identifier: LGPL-3.0-or-later */
+
+//! Watch mode: re-lint domain XMLs whenever they, or validators, change.
+//!
+//! Directories rather than files are watched, because editors often save a file by writing a
+//! new one and renaming it over the original.
+
+use crate::batch;
+use inotify::{EventMask, Inotify, WatchMask};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use virt_lint::*;
+
+/// Changes to files with these extensions trigger re-linting
+const EXTENSIONS: [&str; 3] = ["xml", "lua", "py"];
+
+/// How long to wait for more events after a change, e.g. when a file is saved in several steps
+const SETTLE: Duration = Duration::from_millis(100);
+
+fn watch_mask() -> WatchMask {
+    WatchMask::CLOSE_WRITE
+        | WatchMask::MOVED_TO
+        | WatchMask::MOVED_FROM
+        | WatchMask::CREATE
+        | WatchMask::DELETE
+}
+
+/// Add watches for `dir` and its subdirectories. Adding a watch for an already watched
+/// directory is a no-op.
+fn watch_dir(inotify: &mut Inotify, dir: &Path) -> Result<(), String> {
+    inotify
+        .watches()
+        .add(dir, watch_mask())
+        .map_err(|e| format!("Unable to watch {}: {e}", dir.display()))?;
+
+    let entries = fs::read_dir(dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+    for entry in entries.flatten() {
+        if entry.path().is_dir() {
+            watch_dir(inotify, &entry.path())?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Watch directories containing `paths`, and validator directories.
+fn watch_all(inotify: &mut Inotify, vl: &VirtLint, paths: &[PathBuf]) -> Result<(), String> {
+    let mut dirs: Vec<PathBuf> = paths.iter().filter(|p| p.is_dir()).cloned().collect();
+
+    // Files (and glob patterns) are watched via their directories
+    if let Ok(files) = batch::expand_paths(paths) {
+        dirs.extend(
+            files
+                .iter()
+                .filter_map(|f| f.parent().map(Path::to_path_buf)),
+        );
+    }
+
+    dirs.extend(
+        VirtLint::validator_paths(vl.config())
+            .into_iter()
+            .filter(|p| p.is_dir()),
+    );
+
+    for dir in dirs {
+        if dir.as_os_str().is_empty() {
+            watch_dir(inotify, Path::new("."))?;
+        } else {
+            watch_dir(inotify, &dir)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Describe warning `w`, leaving out line numbers which change as the domain XML is edited.
+fn describe(w: &VirtLintWarning) -> String {
+    let (_, _, level, msg) = w.get();
+    let xpaths: Vec<&str> = w.locations().iter().map(|l| l.xpath()).collect();
+
+    if xpaths.is_empty() {
+        format!("{level}[{}]: {msg}", w.id())
+    } else {
+        format!("{level}[{}]: {msg} at {}", w.id(), xpaths.join(", "))
+    }
+}
+
+/// Lint `paths` and print differences from `previous` results, which are then updated.
+fn lint(
+    vl: &VirtLint,
+    paths: &[PathBuf],
+    tags: &[String],
+    error_on_no_connect: bool,
+    jobs: usize,
+    previous: &mut HashMap<String, Vec<String>>,
+) {
+    let files = match batch::expand_paths(paths) {
+        Ok(f) => f,
+        Err(e) => {
+            eprintln!("{e}");
+            return;
+        }
+    };
+
+    let mut inputs: Vec<batch::Input> = Vec::new();
+    for path in files {
+        match fs::read_to_string(&path) {
+            Ok(domxml) => inputs.push(batch::Input::from_file(path, domxml)),
+            Err(e) => eprintln!("{}: Unable to read the file: {e}", path.display()),
+        }
+    }
+
+    let results = batch::lint_all(vl, &inputs, tags, error_on_no_connect, jobs);
+
+    for name in previous.keys().cloned().collect::<Vec<String>>() {
+        if !inputs.iter().any(|i| i.name() == name) {
+            println!("{name}: removed");
+            previous.remove(&name);
+        }
+    }
+
+    for (input, result) in inputs.iter().zip(results) {
+        let report = match result {
+            Ok(r) => r,
+            Err(e) => {
+                // Most likely saved in the middle of editing, keep the previous results
+                eprintln!("{}: {e}", input.name());
+                continue;
+            }
+        };
+
+        // Most likely a validator being edited
+        for r in report.rules() {
+            if let Some(error) = r.failed() {
+                eprintln!("{}: failed[{}]: {error}", input.name(), r.id());
+            }
+        }
+
+        let current: Vec<String> = report.warnings().iter().map(describe).collect();
+        let mut fixed: Vec<String> = previous.get(input.name()).cloned().unwrap_or_default();
+        let mut new: Vec<String> = Vec::new();
+
+        for w in &current {
+            match fixed.iter().position(|f| f == w) {
+                Some(i) => {
+                    fixed.remove(i);
+                }
+                None => new.push(w.clone()),
+            }
+        }
+
+        if new.is_empty() && fixed.is_empty() && previous.contains_key(input.name()) {
+            println!("{}: no changes ({} warnings)", input.name(), current.len());
+        } else {
+            println!(
+                "{}: {} new, {} fixed ({} warnings)",
+                input.name(),
+                new.len(),
+                fixed.len(),
+                current.len()
+            );
+            fixed.iter().for_each(|w| println!("- {w}"));
+            new.iter().for_each(|w| println!("+ {w}"));
+        }
+
+        previous.insert(input.name().to_string(), current);
+    }
+}
+
+fn relevant(mask: EventMask, name: Option<&std::ffi::OsStr>) -> bool {
+    if mask.contains(EventMask::ISDIR) {
+        return true;
+    }
+
+    name.map(Path::new)
+        .and_then(|n| n.extension())
+        .is_some_and(|e| EXTENSIONS.iter().any(|x| e == *x))
+}
+
+/// Lint `paths` and then again each time they or validators change, until interrupted. The
+/// very same `vl` is used for all runs, so that capabilities are fetched only once.
+pub fn watch(
+    vl: &VirtLint,
+    paths: &[PathBuf],
+    tags: &[String],
+    error_on_no_connect: bool,
+    jobs: usize,
+) -> Result<u8, String> {
+    let mut inotify = Inotify::init().map_err(|e| format!("Unable to initialize inotify: {e}"))?;
+    let mut buffer = [0; 4096];
+    let mut previous: HashMap<String, Vec<String>> = HashMap::new();
+
+    watch_all(&mut inotify, vl, paths)?;
+    lint(vl, paths, tags, error_on_no_connect, jobs, &mut previous);
+
+    loop {
+        let events = inotify
+            .read_events_blocking(&mut buffer)
+            .map_err(|e| format!("Unable to read inotify events: {e}"))?;
+
+        if !events.into_iter().any(|e| relevant(e.mask, e.name)) {
+            continue;
+        }
+
+        // Drain events generated while the change settles
+        std::thread::sleep(SETTLE);
+        while let Ok(events) = inotify.read_events(&mut buffer) {
+            if events.into_iter().next().is_none() {
+                break;
+            }
+        }
+
+        // New directories might have been created, or watched ones removed
+        if let Err(e) = watch_all(&mut inotify, vl, paths) {
+            eprintln!("{e}");
+        }
+
+        println!();
+        lint(vl, paths, tags, error_on_no_connect, jobs, &mut previous);
+    }
+}
@@ -1,3 +1,17 @@
+-- Host provides a suitable emulator
+--
+-- Checks whether the host has an emulator (and accelerator) matching the
+-- architecture, machine type and virtualization type requested by the domain.
+--
+-- Rationale: Without a suitable emulator the domain fails to start.
+--
+-- Level: error
+-- Needs: capabilities, domain-capabilities
+--
+-- References:
+-- - https://libvirt.org/formatcaps.html
+-- - https://libvirt.org/formatdomaincaps.html
+
 local emit_warning = false
 
 local domcaps = vl:domcaps_xpath("/")
@@ -5,6 +19,11 @@ local domcaps = vl:domcaps_xpath("/")
 -- satisfactory (emulator, arch, machine, virttype) tuple and libvirt found it.
 -- But okay, try harder.
 if domcaps == nil or domcaps[1] == nil then
+    if vl:caps_xml() == nil then
+        -- no capabilities
+        return
+    end
+
     local xpath = ""
     local emulator = vl:dom_xpath("//domain/devices/emulator/text()")
     local arch = vl:dom_xpath("//domain/os/type/@arch")
@@ -36,6 +55,15 @@ if domcaps == nil or domcaps[1] == nil then
 end
 
 if emit_warning then
+    local function value(xpath)
+        local ret = vl:dom_xpath(xpath)
+        return ret ~= nil and ret[1] or ""
+    end
+
     vl:add_warning(vl.WarningDomain_Domain, vl.WarningLevel_Error,
-                   "No suitable emulator found")
+                   "No suitable {domain_type} emulator found for {arch} ({machine})",
+                   "//domain/os/type", nil,
+                   { domain_type = value("//domain/@type"),
+                     arch = value("//domain/os/type/@arch"),
+                     machine = value("//domain/os/type/@machine") })
 end
@@ -1,14 +1,37 @@
+-- Domain fits into a host NUMA node
+--
+-- Checks whether the memory of the domain is smaller than the memory of the
+-- biggest NUMA node of the host.
+--
+-- Rationale: Memory of a domain spanning several NUMA nodes is (partially)
+-- remote to its vCPUs, which hurts performance.
+--
+-- Level: error
+-- Needs: capabilities
+--
+-- References:
+-- - https://libvirt.org/formatdomain.html#numa-node-tuning
+
 local would_fit = false
-local dom_mem = tonumber(vl:dom_xpath("//domain/memory/text()")[1])
-local numa_mems = vl:caps_xpath("//capabilities/host/topology/cells/cell/memory/text()")
+local dom_mem = vl:dom_memory_kib("//domain/memory")
+local numa_mems = vl:caps_memory_kib("//capabilities/host/topology/cells/cell/memory")
+local largest = nil
+
+if numa_mems == nil then
+    -- no capabilities
+    return
+end
 
 for _, node in ipairs(numa_mems) do
-    if tonumber(node) > dom_mem then
+    if node > dom_mem then
         would_fit = true
     end
+    largest = math.max(largest or 0, node)
 end
 
 if not would_fit then
     vl:add_warning(vl.WarningDomain_Domain, vl.WarningLevel_Error,
-                   "Domain would not fit into any host NUMA node")
+                   "Domain memory ({requested_kib} KiB) would not fit into any host NUMA node",
+                   "//domain/memory", nil,
+                   { requested_kib = dom_mem, largest_node_kib = largest })
 end
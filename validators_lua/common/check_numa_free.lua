@@ -1,6 +1,28 @@
+-- Enough free memory on a host NUMA node
+--
+-- Checks whether there is a NUMA node on the host with enough free memory to fit
+-- the whole domain. The current state of the host is considered, hence a
+-- connection is needed.
+--
+-- Rationale: If no NUMA node has enough free memory, the domain either fails to
+-- start (with strict memory placement) or its memory is spread across NUMA nodes.
+--
+-- Level: error
+-- Needs: connection, capabilities
+--
+-- References:
+-- - https://libvirt.org/formatdomain.html#numa-node-tuning
+
 local would_fit = false
-local dom_mem = tonumber(vl:dom_xpath("//domain/memory/text()")[1])
+local dom_mem = vl:dom_memory_kib("//domain/memory")
 local node_ids = vl:caps_xpath("//capabilities/host/topology/cells/cell/@id")
+local free = {}
+local largest = nil
+
+if node_ids == nil then
+    -- no capabilities
+    return
+end
 
 for _, node in ipairs(node_ids) do
     local node_free = vl:get_cells_free_memory(node, 1)
@@ -10,14 +32,22 @@ for _, node in ipairs(node_ids) do
         return
     end
 
-    if node_free[1] > dom_mem then
+    -- free memory is in bytes
+    local node_kib = node_free[1] // 1024
+
+    if node_kib > dom_mem then
         would_fit = true
         break
     end
+
+    free[#free + 1] = node_kib
+    largest = math.max(largest or 0, node_kib)
 end
 
 if not would_fit then
     vl:add_warning(vl.WarningDomain_Domain, vl.WarningLevel_Error,
-                   "Not enough free memory on any NUMA node")
+                   "Not enough free memory ({requested_kib} KiB) on any NUMA node",
+                   "//domain/memory", nil,
+                   { requested_kib = dom_mem, free_kib = free,
+                     largest_free_kib = largest })
 end
-
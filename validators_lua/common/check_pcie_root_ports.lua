@@ -1,3 +1,18 @@
+-- Free PCIe root ports for hotplug
+--
+-- Checks whether a Q35 domain has at least one pcie-root-port controller that is
+-- not occupied by a device.
+--
+-- Rationale: On Q35 machine types, PCIe devices can be hotplugged only into free
+-- root ports, which can not be added to a running domain.
+--
+-- Level: notice
+-- Needs: none
+--
+-- References:
+-- - https://libvirt.org/pci-hotplug.html
+-- - https://libvirt.org/formatdomain.html#controllers
+
 local has_free_root_port = false
 
 local virttype = vl:dom_xpath("//domain/@type")
@@ -15,11 +30,15 @@ if machine == nil or string.find(machine[1], "q35") == nil then
 end
 
 local taken = {}
+local occupied = {}
 
-local pcie_chassis = vl:dom_xpath("//domain/devices/controller[@type='pci' and @model='pcie-root-port']/target/@chassis")
+local root_ports_xpath = "//domain/devices/controller[@type='pci' and @model='pcie-root-port']"
+local pcie_chassis = vl:dom_xpath(root_ports_xpath .. "/target/@chassis")
 if pcie_chassis ~= nil then
     for _, v in ipairs(pcie_chassis) do
-        taken[tonumber(v)] = -1 end
+        taken[tonumber(v)] = -1
+        occupied[#occupied + 1] = tonumber(v)
+    end
 end
 
 -- Firstly, remove obviously taken root ports
@@ -40,6 +59,19 @@ for _, v in pairs(taken) do
 end
 
 if not has_free_root_port then
+    -- Point at the (all taken) root ports, or at <devices/> if there are none
+    local xpath = pcie_chassis ~= nil and root_ports_xpath or "//domain/devices"
+
+    local fix = {
+        description = "Add a pcie-root-port controller",
+        edits = {
+            { op = "insert", xpath = "//domain/devices",
+              xml = "<controller type='pci' model='pcie-root-port'/>" },
+        },
+    }
+
     vl:add_warning(vl.WarningDomain_Domain, vl.WarningLevel_Notice,
-                   "No free PCIe root ports found, hotplug might be not possible")
+                   "No free PCIe root ports found (occupied: {occupied_chassis}), "
+                   .. "hotplug might be not possible",
+                   xpath, fix, { occupied_chassis = occupied })
 end
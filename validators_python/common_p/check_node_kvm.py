@@ -1,3 +1,18 @@
+"""Host provides a suitable emulator
+
+Checks whether the host has an emulator (and accelerator) matching the
+architecture, machine type and virtualization type requested by the domain.
+
+Rationale: Without a suitable emulator the domain fails to start.
+
+Level: error
+Needs: capabilities, domain-capabilities
+
+References:
+- https://libvirt.org/formatcaps.html
+- https://libvirt.org/formatdomaincaps.html
+"""
+
 emit_warning = False
 
 domcaps = vl.domcaps_xpath("/")
@@ -36,6 +51,17 @@ if not domcaps or not domcaps[0]:
 
     emit_warning = vl.caps_xpath(top_xpath)
 
+def value(xpath):
+    ret = vl.dom_xpath(xpath)
+    return ret[0] if ret else ""
+
 if emit_warning:
+    data = {
+        "domain_type": value("//domain/@type"),
+        "arch": value("//domain/os/type/@arch"),
+        "machine": value("//domain/os/type/@machine"),
+    }
+
     vl.add_warning(vl.WarningDomain_Domain, vl.WarningLevel_Error,
-                   "No suitable emulator found")
+                   "No suitable {domain_type} emulator found for {arch} ({machine})",
+                   "//domain/os/type", data=data)
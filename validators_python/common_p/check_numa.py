@@ -1,11 +1,38 @@
-would_fit = False
-dom_mem = int(vl.dom_xpath("//domain/memory/text()")[0])
-numa_mems = vl.caps_xpath("//capabilities/host/topology/cells/cell/memory/text()")
+"""Domain fits into a host NUMA node
 
-for node in numa_mems:
-    if int(node) > dom_mem:
-        would_fit = True
+Checks whether the memory of the domain is smaller than the memory of the
+biggest NUMA node of the host.
 
-if not would_fit:
-    vl.add_warning(vl.WarningDomain_Domain, vl.WarningLevel_Error,
-                   "Domain would not fit into any host NUMA node")
+Rationale: Memory of a domain spanning several NUMA nodes is (partially)
+remote to its vCPUs, which hurts performance.
+
+Level: error
+Needs: capabilities
+
+References:
+- https://libvirt.org/formatdomain.html#numa-node-tuning
+"""
+
+def check():
+    would_fit = False
+    dom_mem = vl.dom_memory_kib("//domain/memory")
+    numa_mems = vl.caps_memory_kib("//capabilities/host/topology/cells/cell/memory")
+
+    if numa_mems is None:
+        # no capabilities
+        return
+
+    for node in numa_mems:
+        if node > dom_mem:
+            would_fit = True
+
+    if not would_fit:
+        data = {"requested_kib": dom_mem}
+        if numa_mems:
+            data["largest_node_kib"] = max(numa_mems)
+
+        vl.add_warning(vl.WarningDomain_Domain, vl.WarningLevel_Error,
+                       "Domain memory ({requested_kib} KiB) would not fit into any host NUMA node",
+                       "//domain/memory", data=data)
+
+check()
@@ -1,24 +1,50 @@
+"""Enough free memory on a host NUMA node
+
+Checks whether there is a NUMA node on the host with enough free memory to fit
+the whole domain. The current state of the host is considered, hence a
+connection is needed.
+
+Rationale: If no NUMA node has enough free memory, the domain either fails to
+start (with strict memory placement) or its memory is spread across NUMA nodes.
+
+Level: error
+Needs: connection, capabilities
+
+References:
+- https://libvirt.org/formatdomain.html#numa-node-tuning
+"""
+
 import sys
 import libvirt
 
 def check():
     would_fit = False
-    dom_mem = int(vl.dom_xpath("//domain/memory/text()")[0])
+    dom_mem = vl.dom_memory_kib("//domain/memory")
     node_ids = vl.caps_xpath("//capabilities/host/topology/cells/cell/@id")
 
     conn = vl.get_libvirt_conn()
-    if not conn:
+    if not conn or node_ids is None:
         return
 
+    free = []
+
     for node in node_ids:
-        node_free = conn.getCellsFreeMemory(int(node), 1)
+        # free memory is in bytes
+        node_kib = conn.getCellsFreeMemory(int(node), 1)[0] // 1024
 
-        if node_free[0] > dom_mem:
+        if node_kib > dom_mem:
             would_fit = True
             exit
 
+        free.append(node_kib)
+
     if not would_fit:
+        data = {"requested_kib": dom_mem, "free_kib": free}
+        if free:
+            data["largest_free_kib"] = max(free)
+
         vl.add_warning(vl.WarningDomain_Domain, vl.WarningLevel_Error,
-                       "Not enough free memory on any NUMA node")
+                       "Not enough free memory ({requested_kib} KiB) on any NUMA node",
+                       "//domain/memory", data=data)
 
 check()
@@ -1,3 +1,19 @@
+"""Free PCIe root ports for hotplug
+
+Checks whether a Q35 domain has at least one pcie-root-port controller that is
+not occupied by a device.
+
+Rationale: On Q35 machine types, PCIe devices can be hotplugged only into free
+root ports, which can not be added to a running domain.
+
+Level: notice
+Needs: none
+
+References:
+- https://libvirt.org/pci-hotplug.html
+- https://libvirt.org/formatdomain.html#controllers
+"""
+
 def check():
     has_free_root_port = False
 
@@ -15,7 +31,8 @@ def check():
 
     taken = {}
 
-    pcie_chassis = vl.dom_xpath("//domain/devices/controller[@type='pci' and @model='pcie-root-port']/target/@chassis")
+    root_ports_xpath = "//domain/devices/controller[@type='pci' and @model='pcie-root-port']"
+    pcie_chassis = vl.dom_xpath(root_ports_xpath + "/target/@chassis")
     if pcie_chassis:
         for v in pcie_chassis:
             taken[int(v)] = -1
@@ -36,7 +53,21 @@ def check():
             break
 
     if not has_free_root_port:
+        # Point at the (all taken) root ports, or at <devices/> if there are none
+        xpath = root_ports_xpath if pcie_chassis else "//domain/devices"
+
+        fix = {
+            "description": "Add a pcie-root-port controller",
+            "edits": [
+                {"op": "insert", "xpath": "//domain/devices",
+                 "xml": "<controller type='pci' model='pcie-root-port'/>"},
+            ],
+        }
+
         vl.add_warning(vl.WarningDomain_Domain, vl.WarningLevel_Notice,
-                       "No free PCIe root ports found, hotplug might be not possible")
+                       "No free PCIe root ports found (occupied: {occupied_chassis}), "
+                       "hotplug might be not possible",
+                       xpath, fix,
+                       data={"occupied_chassis": [int(v) for v in pcie_chassis or []]})
 
 check()
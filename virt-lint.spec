@@ -75,6 +75,7 @@ make install-data DESTDIR=%{buildroot} prefix=%{_prefix}
 
 %files
 %{_libdir}/libvirt_lint.so.*
+%{_datadir}/virt-lint/po
 
 %files devel
 %{_libdir}/libvirt_lint.so